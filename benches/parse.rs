@@ -0,0 +1,15 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const COMPLEX: &str = "{\"device_type\":\"COMPUTER\",\"product\":{\"prod_price\":0,\"prod_url\":\"https://www.landsend.com/products/girls-cardigan-sweater/id_346060?attributes\\\\u003d20746,44257,44371,45134\",\"image_url\":\"s7.landsend.com/is/image/LandsEnd/514110_A519_LF_1HV\"},\"referrer\":{\"type\":\"internal\"},\"location\":{\"countryCode\":840,\"postalCode\":\"73120\",\"metroCode\":\"650\",\"regionCode\":0,\"region\":\"ok\",\"country\":\"usa\"},\"cacheBuster\":\"1589926500852940\",\"cart\":{\"quantity\":0,\"value\":0,\"productIDs\":[]},\"new_user\":false,\"user_agent\":\"{\\\"browser\\\":\\\"CHROME8\\\",\\\"browser_version\\\":\\\"81.0.4044.138\\\",\\\"operating_system\\\":\\\"WINDOWS_10\\\",\\\"device_type\\\":\\\"COMPUTER\\\",\\\"is_mobile_device\\\":\\\"false\\\"}\",\"guid\":\"d27b7979-de44-3fad-9a91-f3cb1c8f7c7a\",\"epoch\":1589926500852940,\"time\":1589926500,\"advertiserId\":22921,\"tdid\":\"4da38f58-e197-47da-99c9-486f7d90bccc\",\"guidHash\":1516801586,\"urlPath\":\"/products/girls-cardigan-sweater/id_346060\",\"mobile\":false,\"customTag\":\"shpic\\\\u003d1\\\\u0026ga_tracking_id\\\\u003dua-37627257-1\\\\u0026dxver\\\\u003d4.0.0\\\\u0026ga_info\\\\u003d{\\\"status\\\":\\\"ok\\\",\\\"ga_tracking_id\\\":\\\"ua-37627257-1\\\",\\\"ga_client_id\\\":\\\"1245476243.1575937452\\\",\\\"shpt\\\":\\\"girls cardigan sweater | lands\\\\u0027 end\\\",\\\"execution_workflow\\\":{\\\"iteration\\\":1,\\\"gettrackingidbyga\\\":\\\"ok\\\",\\\"getclientidbytracker\\\":\\\"ok\\\",\\\"shpt\\\":\\\"ok\\\"}}\\\\u0026shadditional\\\\u003dga_tracking_id\\\\u003dua-37627257-1,shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end,ga_client_id\\\\u003d1245476243.1575937452\\\\u0026fdx\\\\u003d1\\\\u0026shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end\\\\u0026ga_client_id\\\\u003d1245476243.1575937452\",\"ip\":\"68.12.228.152\"}";
+
+fn parse_value_complex(c: &mut Criterion) {
+    c.bench_function("parse_value_complex", |b| b.iter(|| jstr::parse_value(COMPLEX).unwrap().to_owned()));
+}
+
+fn parse_fast_complex(c: &mut Criterion) {
+    let bytes = COMPLEX.as_bytes();
+    c.bench_function("parse_fast_complex", |b| b.iter(|| jstr::parse_fast(bytes).unwrap()));
+}
+
+criterion_group!(benches, parse_value_complex, parse_fast_complex);
+criterion_main!(benches);
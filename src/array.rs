@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::iter::Enumerate;
+use std::slice::Iter;
+
+use crate::{Entry, Error, OwnedArray, OwnedEntry, OwnedObject, OwnedObjectExt, OwnedValue, Value};
+
+/// Helpers for working with the elements of an `Array`.
+pub trait ArrayExt<'a> {
+    /// Maps every element to any number of owned values and collects them, in
+    /// order, into one array.
+    fn flat_map<F: Fn(&Value<'a>) -> Vec<OwnedValue>>(&self, f: F) -> OwnedArray;
+
+    /// Copies the array out with the element at `index` split off, giving that
+    /// element (if `index` is in bounds) and the remaining elements.
+    fn take_at(&self, index: usize) -> (Option<OwnedValue>, OwnedArray);
+
+    /// Maps every element, in order, into a new owned array.
+    fn map<F: Fn(&Value<'a>) -> OwnedValue>(&self, f: F) -> OwnedArray;
+
+    /// Iterates over `(index, element)` pairs, as `Iterator::enumerate` does.
+    fn enumerate(&self) -> Enumerate<Iter<'_, Value<'a>>>;
+
+    /// Builds an object with the elements as values, keyed by what `key_fn`
+    /// gives for each. Keys are used as they are, so they should already be
+    /// escaped, as `Value::as_str` gives them.
+    ///
+    /// Fails with `Error::NotFound` if `key_fn` gives `None` for any element,
+    /// and with `Error::DuplicateKey` and the index of the element if a key
+    /// repeats.
+    fn to_object_with_key<F: Fn(&Value<'a>) -> Option<String>>(&self, key_fn: F) -> Result<OwnedObject, Error>;
+}
+
+impl<'a> ArrayExt<'a> for [Value<'a>] {
+    fn flat_map<F: Fn(&Value<'a>) -> Vec<OwnedValue>>(&self, f: F) -> OwnedArray {
+        return self.iter().flat_map(f).collect();
+    }
+
+    fn take_at(&self, index: usize) -> (Option<OwnedValue>, OwnedArray) {
+        let rest = self
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, v)| v.to_owned())
+            .collect();
+        return (self.get(index).map(Value::to_owned), rest);
+    }
+
+    fn map<F: Fn(&Value<'a>) -> OwnedValue>(&self, f: F) -> OwnedArray {
+        return self.iter().map(f).collect();
+    }
+
+    fn enumerate(&self) -> Enumerate<Iter<'_, Value<'a>>> {
+        return self.iter().enumerate();
+    }
+
+    fn to_object_with_key<F: Fn(&Value<'a>) -> Option<String>>(&self, key_fn: F) -> Result<OwnedObject, Error> {
+        let mut entries: Vec<OwnedEntry> = Vec::with_capacity(self.len());
+        for (i, value) in self.iter().enumerate() {
+            let key = key_fn(value).ok_or(Error::NotFound)?;
+            if entries.iter().any(|e| e.key == key) {
+                return Err(Error::DuplicateKey(key, i));
+            }
+            entries.push(OwnedEntry {
+                key,
+                value: value.to_owned(),
+            });
+        }
+        return Ok(entries.into_boxed_slice());
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Merges two arrays of objects, matching elements on the value of `key`.
+    ///
+    /// Each element of `other` is merged into the first element of this array
+    /// with the same value for `key`, its fields replacing any already there,
+    /// or appended if there's no such element. A value that isn't an array is
+    /// treated as an empty one.
+    pub fn merge_arrays_by_key(&self, key: &str, other: &OwnedValue) -> OwnedArray {
+        let mut merged: Vec<OwnedValue> = self.as_array().unwrap_or(&[]).iter().map(Value::to_owned).collect();
+        let theirs: &[OwnedValue] = match other {
+            OwnedValue::Array(a) => a,
+            _ => &[],
+        };
+
+        for value in theirs.iter() {
+            let existing = value.get(key).and_then(|id| merged.iter_mut().find(|m| m.get(key) == Some(id)));
+            match (existing, value) {
+                (Some(OwnedValue::Object(mine)), OwnedValue::Object(fields)) => {
+                    *mine = mine.merge_strategy(fields, |_, _, r| r.clone());
+                }
+                _ => merged.push(value.clone()),
+            }
+        }
+        return merged.into_boxed_slice();
+    }
+
+    /// Groups an array of objects by their value for `key`, as SQL's
+    /// `GROUP BY` does. Each group is named by the value's string contents,
+    /// or its compact JSON if it isn't a string, and holds the objects in
+    /// order with `key` removed.
+    ///
+    /// Elements that aren't objects or don't have `key` are skipped, and a
+    /// value that isn't an array gives no groups.
+    pub fn aggregate(&self, key: &str) -> HashMap<String, Vec<OwnedValue>> {
+        let mut groups: HashMap<String, Vec<OwnedValue>> = HashMap::new();
+        for value in self.as_array().unwrap_or(&[]).iter() {
+            let (o, group) = match (value, value.get(key)) {
+                (Value::Object(o), Some(Value::String(s))) => (o, s.to_string()),
+                (Value::Object(o), Some(other)) => (o, other.to_string()),
+                _ => continue,
+            };
+            let rest = o.iter().filter(|e| e.key != key).map(Entry::to_owned).collect();
+            groups.entry(group).or_default().push(OwnedValue::Object(rest));
+        }
+        return groups;
+    }
+
+    /// Splits an array into chunks of `size` elements, the last of which may
+    /// be shorter. A `size` of 0, or a value that isn't an array, gives no
+    /// chunks.
+    pub fn chunk_array(&self, size: usize) -> Vec<OwnedArray> {
+        if size == 0 {
+            return vec![];
+        }
+        return self
+            .as_array()
+            .unwrap_or(&[])
+            .chunks(size)
+            .map(|chunk| chunk.iter().map(Value::to_owned).collect())
+            .collect();
+    }
+
+    /// Alternates the elements of this array with those of `other`, starting
+    /// with this one, and appends whatever is left of the longer array. A
+    /// value that isn't an array is treated as an empty one.
+    pub fn interleave(&self, other: &OwnedArray) -> OwnedArray {
+        let mine = self.as_array().unwrap_or(&[]);
+        let mut interleaved = Vec::with_capacity(mine.len() + other.len());
+        for i in 0..mine.len().max(other.len()) {
+            if let Some(value) = mine.get(i) {
+                interleaved.push(value.to_owned());
+            }
+            if let Some(value) = other.get(i) {
+                interleaved.push(value.clone());
+            }
+        }
+        return interleaved.into_boxed_slice();
+    }
+
+    /// Copies every distinct element of this array and then of `other`, each
+    /// once, at its first appearance. Elements are compared as they're
+    /// written, so `1` and `1.0` are different. A value that isn't an array is
+    /// treated as an empty one.
+    pub fn union_arrays(&self, other: &OwnedArray) -> OwnedArray {
+        let mut union: Vec<OwnedValue> = vec![];
+        let mine = self.as_array().unwrap_or(&[]).iter().map(Value::to_owned);
+        for value in mine.chain(other.iter().cloned()) {
+            if !union.contains(&value) {
+                union.push(value);
+            }
+        }
+        return union.into_boxed_slice();
+    }
+
+    /// Copies the elements of this array that are also in `other`, in order.
+    /// Repeated elements are kept, so if every element is in `other` this
+    /// gives a copy of the array. Elements are compared as `union_arrays`
+    /// compares them.
+    pub fn intersect_arrays(&self, other: &OwnedArray) -> OwnedArray {
+        return self
+            .as_array()
+            .unwrap_or(&[])
+            .iter()
+            .map(Value::to_owned)
+            .filter(|value| other.contains(value))
+            .collect();
+    }
+
+    /// Copies the elements of this array that aren't in `other`, in order,
+    /// repeated ones included. Elements are compared as `union_arrays`
+    /// compares them.
+    pub fn diff_arrays(&self, other: &OwnedArray) -> OwnedArray {
+        return self
+            .as_array()
+            .unwrap_or(&[])
+            .iter()
+            .map(Value::to_owned)
+            .filter(|value| !other.contains(value))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, ArrayExt, Error, OwnedArray, OwnedValue, Value};
+
+    #[test]
+    fn flat_map_test() {
+        let v = parse_value("[1,2,3]").unwrap();
+        let a = v.as_array().unwrap();
+
+        let doubled = a.flat_map(|v| match v {
+            Value::Number(n) => {
+                let n = n.parse::<i64>().unwrap();
+                vec![OwnedValue::Number(n.to_string()), OwnedValue::Number((n * 2).to_string())]
+            }
+            _ => vec![],
+        });
+
+        assert_eq!(doubled.len(), a.len() * 2);
+        assert_eq!(OwnedValue::Array(doubled), parse_value("[1,2,2,4,3,6]").unwrap().to_owned());
+    }
+
+    #[test]
+    fn take_at_test() {
+        let v = parse_value("[1,\"b\",3,4]").unwrap();
+        let a = v.as_array().unwrap();
+
+        let (taken, rest) = a.take_at(1);
+        assert_eq!(taken, Some(OwnedValue::String("b".to_string())));
+        assert_eq!(OwnedValue::Array(rest), parse_value("[1,3,4]").unwrap().to_owned());
+
+        let (taken, rest) = a.take_at(4);
+        assert_eq!(taken, None);
+        assert_eq!(rest.len(), 4);
+    }
+
+    #[test]
+    fn map_test() {
+        let v = parse_value("[1,2,\"x\",3]").unwrap();
+        let a = v.as_array().unwrap();
+
+        let incremented = a.map(|v| match v {
+            Value::Number(n) => OwnedValue::from(n.parse::<i64>().unwrap() + 1),
+            v => v.to_owned(),
+        });
+        assert_eq!(OwnedValue::Array(incremented), parse_value("[2,3,\"x\",4]").unwrap().to_owned());
+    }
+
+    #[test]
+    fn enumerate_test() {
+        let v = parse_value("[\"a\",true,null]").unwrap();
+        let a = v.as_array().unwrap();
+
+        let pairs: Vec<(usize, &Value)> = a.enumerate().collect();
+        assert_eq!(pairs, vec![(0, &Value::String("a")), (1, &Value::Boolean("true")), (2, &Value::Null)]);
+    }
+
+    #[test]
+    fn to_object_with_key_test() {
+        let id = |v: &Value| Some(v.get("id")?.as_str()?.to_string());
+
+        let v = parse_value("[{\"id\":\"a\"},{\"id\":\"b\",\"n\":1}]").unwrap();
+        let a = v.as_array().unwrap();
+        let expected = parse_value("{\"a\":{\"id\":\"a\"},\"b\":{\"id\":\"b\",\"n\":1}}").unwrap().to_owned();
+        assert_eq!(OwnedValue::Object(a.to_object_with_key(id).unwrap()), expected);
+
+        let v = parse_value("[{\"id\":\"a\"},{\"id\":\"b\"},{\"id\":\"a\"}]").unwrap();
+        let a = v.as_array().unwrap();
+        assert!(matches!(a.to_object_with_key(id), Err(Error::DuplicateKey(k, 2)) if k == "a"));
+
+        let v = parse_value("[{\"id\":\"a\"},{\"id\":1}]").unwrap();
+        let a = v.as_array().unwrap();
+        assert!(matches!(a.to_object_with_key(id), Err(Error::NotFound)));
+    }
+
+    #[test]
+    fn merge_arrays_by_key_test() {
+        let a = parse_value("[{\"id\":1,\"a\":\"x\"}]").unwrap();
+        let b = parse_value("[{\"id\":1,\"b\":\"y\"},{\"id\":2,\"c\":\"z\"}]").unwrap().to_owned();
+        let expected = parse_value("[{\"id\":1,\"a\":\"x\",\"b\":\"y\"},{\"id\":2,\"c\":\"z\"}]").unwrap().to_owned();
+        assert_eq!(OwnedValue::Array(a.merge_arrays_by_key("id", &b)), expected);
+
+        let a = parse_value("[{\"id\":1,\"a\":\"x\"},{\"a\":\"w\"}]").unwrap();
+        let b = parse_value("[{\"id\":1,\"a\":\"y\"},{\"b\":1},2]").unwrap().to_owned();
+        let expected = parse_value("[{\"id\":1,\"a\":\"y\"},{\"a\":\"w\"},{\"b\":1},2]").unwrap().to_owned();
+        assert_eq!(OwnedValue::Array(a.merge_arrays_by_key("id", &b)), expected);
+    }
+
+    #[test]
+    fn aggregate_test() {
+        let shirts = parse_value("[{\"color\":\"red\",\"size\":\"M\",\"price\":20},{\"color\":\"blue\",\"size\":\"S\",\"price\":18},{\"color\":\"red\",\"size\":\"L\",\"price\":22},{\"color\":1,\"size\":\"XL\"},{\"size\":\"XS\"},\"green\"]").unwrap();
+        let groups = shirts.aggregate("color");
+
+        assert_eq!(groups.len(), 3);
+        let red = vec![parse_value("{\"size\":\"M\",\"price\":20}").unwrap().to_owned(), parse_value("{\"size\":\"L\",\"price\":22}").unwrap().to_owned()];
+        assert_eq!(groups["red"], red);
+        assert_eq!(groups["blue"], vec![parse_value("{\"size\":\"S\",\"price\":18}").unwrap().to_owned()]);
+        assert_eq!(groups["1"], vec![parse_value("{\"size\":\"XL\"}").unwrap().to_owned()]);
+
+        assert!(parse_value("{\"color\":\"red\"}").unwrap().aggregate("color").is_empty());
+    }
+
+    #[test]
+    fn chunk_array_test() {
+        let a = parse_value("[0,1,2,3,4,5,6,7,8,9]").unwrap();
+        let chunks = a.chunk_array(3);
+        assert_eq!(chunks.iter().map(|c| c.len()).collect::<Vec<_>>(), vec![3, 3, 3, 1]);
+        assert_eq!(OwnedValue::Array(chunks[1].clone()), parse_value("[3,4,5]").unwrap().to_owned());
+        assert_eq!(OwnedValue::Array(chunks[3].clone()), parse_value("[9]").unwrap().to_owned());
+
+        assert!(a.chunk_array(0).is_empty());
+        let whole = a.chunk_array(10);
+        assert_eq!(whole.len(), 1);
+        assert_eq!(OwnedValue::Array(whole[0].clone()), a.to_owned());
+        assert_eq!(a.chunk_array(25).len(), 1);
+        assert!(parse_value("[]").unwrap().chunk_array(2).is_empty());
+        assert!(parse_value("{\"a\":1}").unwrap().chunk_array(2).is_empty());
+    }
+
+    fn owned_array(s: &str) -> OwnedArray {
+        match parse_value(s).unwrap().to_owned() {
+            OwnedValue::Array(a) => a,
+            _ => panic!("expected an array"),
+        }
+    }
+
+    #[test]
+    fn interleave_test() {
+        let a = parse_value("[1,2,3]").unwrap();
+        let interleaved = a.interleave(&owned_array("[\"a\",\"b\",\"c\"]"));
+        assert_eq!(interleaved, owned_array("[1,\"a\",2,\"b\",3,\"c\"]"));
+
+        assert_eq!(a.interleave(&owned_array("[\"a\"]")), owned_array("[1,\"a\",2,3]"));
+        assert_eq!(a.interleave(&owned_array("[4,5,6,7,8]")), owned_array("[1,4,2,5,3,6,7,8]"));
+        assert_eq!(a.interleave(&owned_array("[]")), owned_array("[1,2,3]"));
+        assert_eq!(parse_value("null").unwrap().interleave(&owned_array("[1]")), owned_array("[1]"));
+    }
+
+    #[test]
+    fn union_arrays_test() {
+        let a = parse_value("[1,\"a\",{\"b\":2},1]").unwrap();
+        assert_eq!(a.union_arrays(&owned_array("[{\"b\":2},3,\"a\",4,3]")), owned_array("[1,\"a\",{\"b\":2},3,4]"));
+        assert_eq!(a.union_arrays(&owned_array("[true,null]")), owned_array("[1,\"a\",{\"b\":2},true,null]"));
+        assert_eq!(a.union_arrays(&owned_array("[1.0]")), owned_array("[1,\"a\",{\"b\":2},1.0]"));
+        assert_eq!(parse_value("[]").unwrap().union_arrays(&owned_array("[2,2]")), owned_array("[2]"));
+    }
+
+    #[test]
+    fn intersect_arrays_test() {
+        let a = parse_value("[1,\"a\",{\"b\":2},1,null]").unwrap();
+        assert!(a.intersect_arrays(&owned_array("[2,\"b\",{\"b\":3}]")).is_empty());
+        assert_eq!(a.intersect_arrays(&owned_array("[null,{\"b\":2},\"a\",1]")), owned_array("[1,\"a\",{\"b\":2},1,null]"));
+        assert_eq!(a.intersect_arrays(&owned_array("[null,1,5]")), owned_array("[1,1,null]"));
+        assert!(parse_value("{}").unwrap().intersect_arrays(&owned_array("[1]")).is_empty());
+    }
+
+    #[test]
+    fn diff_arrays_test() {
+        let a = parse_value("[1,2,3,4]").unwrap();
+        assert_eq!(a.diff_arrays(&owned_array("[2,4]")), owned_array("[1,3]"));
+        assert_eq!(a.diff_arrays(&owned_array("[]")), owned_array("[1,2,3,4]"));
+        assert!(a.diff_arrays(&owned_array("[4,3,2,1,0]")).is_empty());
+
+        let a = parse_value("[{\"id\":1},\"x\",{\"id\":2},\"x\"]").unwrap();
+        assert_eq!(a.diff_arrays(&owned_array("[{\"id\":2}]")), owned_array("[{\"id\":1},\"x\",\"x\"]"));
+    }
+}
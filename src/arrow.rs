@@ -0,0 +1,57 @@
+use crate::Value;
+
+impl<'a> Value<'a> {
+    /// Transposes an array of objects into the column oriented form used by
+    /// Arrow JSON, so `[{"a":1,"b":"x"},{"a":2,"b":"y"}]` becomes
+    /// `{"a":[1,2],"b":["x","y"]}`.
+    ///
+    /// Columns are in the order their keys first appear. A row without some
+    /// key, or that isn't an object, gives `null` in that column. A value that
+    /// isn't an array gives `{}`.
+    pub fn to_arrow_json(&self) -> String {
+        let rows = self.as_array().unwrap_or(&[]);
+
+        let mut columns: Vec<&str> = vec![];
+        for row in rows.iter() {
+            if let Value::Object(o) = row {
+                for entry in o.iter() {
+                    if !columns.contains(&entry.key) {
+                        columns.push(entry.key);
+                    }
+                }
+            }
+        }
+
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|key| {
+                let cells: Vec<String> = rows
+                    .iter()
+                    .map(|row| match row.get(key) {
+                        Some(value) => value.to_string(),
+                        None => "null".to_string(),
+                    })
+                    .collect();
+                format!("\"{}\":[{}]", key, cells.join(","))
+            })
+            .collect();
+        return format!("{{{}}}", columns.join(","));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_value;
+
+    #[test]
+    fn to_arrow_json_test() {
+        let rows = parse_value("[{\"a\":1,\"b\":\"x\"},{\"a\":2,\"b\":\"y\"}]").unwrap();
+        assert_eq!(rows.to_arrow_json(), "{\"a\":[1,2],\"b\":[\"x\",\"y\"]}");
+
+        let rows = parse_value("[{\"a\":1},{\"b\":[true]},3]").unwrap();
+        assert_eq!(rows.to_arrow_json(), "{\"a\":[1,null,null],\"b\":[null,[true],null]}");
+
+        assert_eq!(parse_value("[]").unwrap().to_arrow_json(), "{}");
+        assert_eq!(parse_value("{\"a\":1}").unwrap().to_arrow_json(), "{}");
+    }
+}
@@ -0,0 +1,84 @@
+const STANDARD: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes `bytes` as standard, padded base64.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    return encode_with(bytes, STANDARD, true);
+}
+
+/// Encodes `bytes` as unpadded base64url, as JWTs use.
+pub(crate) fn encode_url(bytes: &[u8]) -> String {
+    return encode_with(bytes, URL_SAFE, false);
+}
+
+/// Decodes unpadded base64url. Returns `None` if `s` holds anything outside
+/// the alphabet or has a length no encoding could give.
+pub(crate) fn decode_url(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let sextet = URL_SAFE.iter().position(|a| a == c)? as u32;
+            n |= sextet << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            decoded.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    return Some(decoded);
+}
+
+fn encode_with(bytes: &[u8], alphabet: &[u8], pad: bool) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else if pad {
+                encoded.push('=');
+            }
+        }
+    }
+    return encoded;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base64::{decode_url, encode, encode_url};
+
+    #[test]
+    fn encode_test() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode(&[0xff, 0xfe]), "//4=");
+    }
+
+    #[test]
+    fn encode_url_test() {
+        assert_eq!(encode_url(&[0xff, 0xfe]), "__4");
+        assert_eq!(encode_url(b"fo"), "Zm8");
+
+        for bytes in [&b""[..], b"f", b"fo", b"foo", b"\x00\xff\x10\x80"].iter() {
+            assert_eq!(decode_url(&encode_url(bytes)).as_deref(), Some(*bytes));
+        }
+    }
+
+    #[test]
+    fn decode_url_test() {
+        assert_eq!(decode_url(""), Some(vec![]));
+        assert_eq!(decode_url("__4"), Some(vec![0xff, 0xfe]));
+        assert_eq!(decode_url("Zm8"), Some(b"fo".to_vec()));
+        assert_eq!(decode_url("Zm9vYmFy"), Some(b"foobar".to_vec()));
+        assert_eq!(decode_url("Zm8="), None);
+        assert_eq!(decode_url("Zm+v"), None);
+        assert_eq!(decode_url("Zm9vY"), None);
+    }
+}
@@ -0,0 +1,157 @@
+use std::iter::FromIterator;
+
+use crate::owned::escape;
+use crate::{OwnedEntry, OwnedObject, OwnedValue};
+
+/// Builds an `OwnedObject` one entry at a time.
+///
+/// Entries are kept in the order they were added. Keys may repeat while
+/// building; `build` keeps the last value for each key at the position where
+/// the key first appeared.
+#[derive(Debug, Default, Clone)]
+pub struct ObjectBuilder {
+    entries: Vec<OwnedEntry>,
+}
+
+impl ObjectBuilder {
+    pub fn new() -> ObjectBuilder {
+        return ObjectBuilder::default();
+    }
+
+    /// Adds an entry. The key is plain text and is escaped as needed.
+    pub fn insert(mut self, key: impl Into<String>, value: OwnedValue) -> ObjectBuilder {
+        self.entries.push(OwnedEntry {
+            key: escape(&key.into()),
+            value,
+        });
+        return self;
+    }
+
+    /// Adds an entry, as `insert` does, only if `condition` is true.
+    pub fn conditional_insert(self, condition: bool, key: impl Into<String>, value: OwnedValue) -> ObjectBuilder {
+        if !condition {
+            return self;
+        }
+        return self.insert(key, value);
+    }
+
+    /// Removes every entry added so far for `key`, which is plain text like
+    /// the keys given to `insert`. Entries added afterwards are kept.
+    pub fn without(mut self, key: &str) -> ObjectBuilder {
+        let key = escape(key);
+        self.entries.retain(|e| e.key != key);
+        return self;
+    }
+
+    /// Adds every entry of `other`, after the ones already added.
+    pub fn merge(mut self, other: OwnedObject) -> ObjectBuilder {
+        self.entries.extend(other.into_vec());
+        return self;
+    }
+
+    /// The number of entries added so far, counting repeated keys.
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn build(self) -> OwnedObject {
+        let mut object: Vec<OwnedEntry> = Vec::with_capacity(self.entries.len());
+        for entry in self.entries {
+            match object.iter_mut().find(|e| e.key == entry.key) {
+                Some(existing) => existing.value = entry.value,
+                None => object.push(entry),
+            }
+        }
+        return object.into_boxed_slice();
+    }
+}
+
+/// Inserts each `(key, value)` pair in turn, as `insert` does.
+impl Extend<(String, OwnedValue)> for ObjectBuilder {
+    fn extend<I: IntoIterator<Item = (String, OwnedValue)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entries.push(OwnedEntry {
+                key: escape(&key),
+                value,
+            });
+        }
+    }
+}
+
+/// Collects `(key, value)` pairs into a builder. `OwnedObject` is a boxed
+/// slice, so collect into a builder and `build` it to get one.
+impl FromIterator<(String, OwnedValue)> for ObjectBuilder {
+    fn from_iter<I: IntoIterator<Item = (String, OwnedValue)>>(iter: I) -> ObjectBuilder {
+        let mut builder = ObjectBuilder::new();
+        builder.extend(iter);
+        return builder;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, ObjectBuilder, OwnedObject, OwnedValue};
+
+    #[test]
+    fn merge_test() {
+        let defaults = match parse_value("{\"b\":20,\"c\":3,\"d\":4}").unwrap().to_owned() {
+            OwnedValue::Object(o) => o,
+            _ => panic!("expected an object"),
+        };
+
+        let builder = ObjectBuilder::new()
+            .insert("a", OwnedValue::Number("1".to_string()))
+            .insert("b", OwnedValue::Number("2".to_string()))
+            .merge(defaults);
+        assert_eq!(builder.len(), 5);
+
+        let expected = parse_value("{\"a\":1,\"b\":20,\"c\":3,\"d\":4}").unwrap().to_owned();
+        assert_eq!(OwnedValue::Object(builder.build()), expected);
+    }
+
+    #[test]
+    fn collect_test() {
+        let pairs = vec![
+            ("a".to_string(), OwnedValue::Number("1".to_string())),
+            ("b".to_string(), OwnedValue::Null),
+        ];
+        let obj: OwnedObject = pairs.into_iter().collect::<ObjectBuilder>().build();
+        assert_eq!(OwnedValue::Object(obj), parse_value("{\"a\":1,\"b\":null}").unwrap().to_owned());
+
+        let mut builder = ObjectBuilder::new().insert("a", OwnedValue::Null);
+        builder.extend(vec![("a".to_string(), OwnedValue::Number("2".to_string())), ("c\"".to_string(), OwnedValue::Null)]);
+        assert_eq!(
+            OwnedValue::Object(builder.build()),
+            parse_value("{\"a\":2,\"c\\\"\":null}").unwrap().to_owned()
+        );
+    }
+
+    #[test]
+    fn conditional_insert_test() {
+        let build = |include_age: bool| {
+            ObjectBuilder::new()
+                .insert("name", OwnedValue::from("a"))
+                .conditional_insert(include_age, "age", OwnedValue::from(30i64))
+                .build()
+        };
+
+        assert_eq!(OwnedValue::Object(build(true)), parse_value("{\"name\":\"a\",\"age\":30}").unwrap().to_owned());
+        assert_eq!(OwnedValue::Object(build(false)), parse_value("{\"name\":\"a\"}").unwrap().to_owned());
+    }
+
+    #[test]
+    fn without_test() {
+        let builder = ObjectBuilder::new()
+            .insert("a", OwnedValue::from(1i64))
+            .insert("b\"", OwnedValue::from(2i64))
+            .insert("c", OwnedValue::from(3i64))
+            .without("b\"")
+            .without("missing");
+        assert_eq!(builder.len(), 2);
+        assert_eq!(OwnedValue::Object(builder.build()), parse_value("{\"a\":1,\"c\":3}").unwrap().to_owned());
+    }
+}
@@ -0,0 +1,81 @@
+use std::convert::TryFrom;
+
+use crate::{Error, Value};
+
+/// Fails with `Error::WrongType` unless the value is a number that fits.
+impl<'s, 'a> TryFrom<&'s Value<'a>> for i64 {
+    type Error = Error;
+
+    fn try_from(value: &'s Value<'a>) -> Result<i64, Error> {
+        return value.as_i64().ok_or_else(|| Error::WrongType("i64", value.type_name()));
+    }
+}
+
+/// Fails with `Error::WrongType` unless the value is a number that fits.
+impl<'s, 'a> TryFrom<&'s Value<'a>> for u64 {
+    type Error = Error;
+
+    fn try_from(value: &'s Value<'a>) -> Result<u64, Error> {
+        if let Value::Number(n) = value {
+            if let Ok(n) = n.parse() {
+                return Ok(n);
+            }
+        }
+        return Err(Error::WrongType("u64", value.type_name()));
+    }
+}
+
+/// Fails with `Error::WrongType` unless the value is a number.
+impl<'s, 'a> TryFrom<&'s Value<'a>> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &'s Value<'a>) -> Result<f64, Error> {
+        if let Value::Number(n) = value {
+            if let Ok(n) = n.parse() {
+                return Ok(n);
+            }
+        }
+        return Err(Error::WrongType("f64", value.type_name()));
+    }
+}
+
+/// Fails with `Error::WrongType` unless the value is `true` or `false`.
+impl<'s, 'a> TryFrom<&'s Value<'a>> for bool {
+    type Error = Error;
+
+    fn try_from(value: &'s Value<'a>) -> Result<bool, Error> {
+        return value.as_bool_strict().ok_or_else(|| Error::WrongType("boolean", value.type_name()));
+    }
+}
+
+/// Gives the contents of a string, escapes included, as `as_str` does.
+impl<'s, 'a> TryFrom<&'s Value<'a>> for &'a str {
+    type Error = Error;
+
+    fn try_from(value: &'s Value<'a>) -> Result<&'a str, Error> {
+        return value.as_str().ok_or_else(|| Error::WrongType("string", value.type_name()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{parse_value, Error};
+
+    #[test]
+    fn try_from_test() {
+        let value = parse_value("[42,-1,2.5,true,\"a\\\"b\",null]").unwrap();
+        let a = value.as_array().unwrap();
+
+        assert_eq!(i64::try_from(&a[0]).unwrap(), 42);
+        assert_eq!(u64::try_from(&a[0]).unwrap(), 42);
+        assert!(matches!(u64::try_from(&a[1]), Err(Error::WrongType("u64", "number"))));
+        assert!(matches!(i64::try_from(&a[2]), Err(Error::WrongType("i64", "number"))));
+        assert_eq!(f64::try_from(&a[2]).unwrap(), 2.5);
+        assert!(bool::try_from(&a[3]).unwrap());
+        assert_eq!(<&str>::try_from(&a[4]).unwrap(), "a\\\"b");
+        assert!(matches!(<&str>::try_from(&a[5]), Err(Error::WrongType("string", "null"))));
+        assert!(matches!(bool::try_from(&a[4]), Err(Error::WrongType("boolean", "string"))));
+    }
+}
@@ -0,0 +1,289 @@
+use std::slice::Iter;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::owned::unescape;
+use crate::{Entry, Error, Value};
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Error {
+        return Error::Decode(msg.to_string());
+    }
+}
+
+/// Deserializes a `T` from a parsed value. Strings without escapes are
+/// borrowed from the source document where `T` allows it.
+pub fn from_value<'de, T: Deserialize<'de>>(value: &Value<'de>) -> Result<T, Error> {
+    return T::deserialize(ValueDeserializer::new(value));
+}
+
+/// A `serde::Deserializer` reading from a `Value`.
+///
+/// Objects are read as maps or structs and arrays as sequences or tuples.
+/// Enums are read as `ValueSerializer` writes them: a unit variant is a
+/// string holding its name, and other variants are an object holding their
+/// contents under their name.
+#[derive(Debug, Clone, Copy)]
+pub struct ValueDeserializer<'v, 'de> {
+    value: &'v Value<'de>,
+}
+
+impl<'v, 'de> ValueDeserializer<'v, 'de> {
+    pub fn new(value: &'v Value<'de>) -> ValueDeserializer<'v, 'de> {
+        return ValueDeserializer { value };
+    }
+}
+
+/// Gives the entries of an object, in order, to a visitor as a map.
+pub struct ObjectDeserializer<'v, 'de> {
+    entries: Iter<'v, Entry<'de>>,
+    value: Option<&'v Value<'de>>,
+}
+
+impl<'v, 'de> ObjectDeserializer<'v, 'de> {
+    pub fn new(object: &'v [Entry<'de>]) -> ObjectDeserializer<'v, 'de> {
+        return ObjectDeserializer {
+            entries: object.iter(),
+            value: None,
+        };
+    }
+}
+
+struct ArrayDeserializer<'v, 'de> {
+    values: Iter<'v, Value<'de>>,
+}
+
+struct EnumDeserializer<'v, 'de> {
+    variant: &'de str,
+    value: Option<&'v Value<'de>>,
+}
+
+fn visit_text<'de, V: Visitor<'de>>(s: &'de str, visitor: V) -> Result<V::Value, Error> {
+    if s.contains('\\') {
+        return visitor.visit_string(unescape(s));
+    }
+    return visitor.visit_borrowed_str(s);
+}
+
+impl<'v, 'de> de::Deserializer<'de> for ValueDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        return match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Boolean(b) => visitor.visit_bool(*b == "true"),
+            Value::Number(n) => {
+                if let Ok(i) = n.parse::<i64>() {
+                    visitor.visit_i64(i)
+                } else if let Ok(u) = n.parse::<u64>() {
+                    visitor.visit_u64(u)
+                } else {
+                    match n.parse::<f64>() {
+                        Ok(f) => visitor.visit_f64(f),
+                        Err(_) => Err(Error::Decode(format!("{} is not a number", n))),
+                    }
+                }
+            }
+            Value::String(s) => visit_text(s, visitor),
+            Value::Array(a) => visitor.visit_seq(ArrayDeserializer { values: a.iter() }),
+            Value::Object(o) => visitor.visit_map(ObjectDeserializer::new(o)),
+        };
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        return match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        };
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        return visitor.visit_newtype_struct(self);
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        return match self.value {
+            Value::String(s) => visitor.visit_enum(EnumDeserializer { variant: s, value: None }),
+            Value::Object(o) if o.len() == 1 => visitor.visit_enum(EnumDeserializer {
+                variant: o[0].key,
+                value: Some(&o[0].value),
+            }),
+            other => Err(Error::Decode(format!("expected an enum, found {}", other.type_name()))),
+        };
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'v, 'de> de::MapAccess<'de> for ObjectDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        let entry = match self.entries.next() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        self.value = Some(&entry.value);
+        return seed.deserialize(ValueDeserializer::new(&Value::String(entry.key))).map(Some);
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::Decode("map value read before its key".to_string()))?;
+        return seed.deserialize(ValueDeserializer::new(value));
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        return Some(self.entries.len());
+    }
+}
+
+impl<'v, 'de> de::SeqAccess<'de> for ArrayDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        return match self.values.next() {
+            Some(value) => seed.deserialize(ValueDeserializer::new(value)).map(Some),
+            None => Ok(None),
+        };
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        return Some(self.values.len());
+    }
+}
+
+impl<'v, 'de> de::EnumAccess<'de> for EnumDeserializer<'v, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<(T::Value, Self), Error> {
+        let variant = seed.deserialize(ValueDeserializer::new(&Value::String(self.variant)))?;
+        return Ok((variant, self));
+    }
+}
+
+impl<'v, 'de> EnumDeserializer<'v, 'de> {
+    fn content(&self) -> Result<ValueDeserializer<'v, 'de>, Error> {
+        return match self.value {
+            Some(value) => Ok(ValueDeserializer::new(value)),
+            None => Err(Error::Decode(format!("variant {} has no contents", self.variant))),
+        };
+    }
+}
+
+impl<'v, 'de> de::VariantAccess<'de> for EnumDeserializer<'v, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        return match self.value {
+            None | Some(Value::Null) => Ok(()),
+            Some(_) => Err(Error::Decode(format!("unit variant {} has contents", self.variant))),
+        };
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        return seed.deserialize(self.content()?);
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        return de::Deserializer::deserialize_seq(self.content()?, visitor);
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        return de::Deserializer::deserialize_map(self.content()?, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::{from_value, parse_value, Error};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Role {
+        Admin,
+        Guest(u8),
+        Custom { name: String },
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address<'a> {
+        city: &'a str,
+        zip: Option<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record<'a> {
+        flag: bool,
+        small: i8,
+        medium: i16,
+        int: i32,
+        long: i64,
+        byte: u8,
+        ushort: u16,
+        uint: u32,
+        ulong: u64,
+        single: f32,
+        double: f64,
+        initial: char,
+        name: String,
+        missing: Option<String>,
+        #[serde(borrow)]
+        address: Address<'a>,
+        pair: (u8, String),
+        roles: Vec<Role>,
+    }
+
+    #[test]
+    fn deserialize_struct_test() {
+        let s = "{\"flag\":true,\"small\":-8,\"medium\":-16,\"int\":-32,\"long\":-64,\"byte\":8,\"ushort\":16,\"uint\":32,\"ulong\":18446744073709551615,\"single\":1.5,\"double\":-2.5e3,\"initial\":\"x\",\"name\":\"a \\\"b\\\"\",\"missing\":null,\"address\":{\"city\":\"c\",\"zip\":12345},\"pair\":[1,\"one\"],\"roles\":[\"Admin\",{\"Guest\":2},{\"Custom\":{\"name\":\"r\"}}],\"extra\":[1]}";
+        let value = parse_value(s).unwrap();
+        let record: Record = from_value(&value).unwrap();
+
+        assert_eq!(
+            record,
+            Record {
+                flag: true,
+                small: -8,
+                medium: -16,
+                int: -32,
+                long: -64,
+                byte: 8,
+                ushort: 16,
+                uint: 32,
+                ulong: u64::MAX,
+                single: 1.5,
+                double: -2500.0,
+                initial: 'x',
+                name: "a \"b\"".to_string(),
+                missing: None,
+                address: Address { city: "c", zip: Some(12345) },
+                pair: (1, "one".to_string()),
+                roles: vec![Role::Admin, Role::Guest(2), Role::Custom { name: "r".to_string() }],
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_error_test() {
+        let value = parse_value("{\"city\":1}").unwrap();
+        assert!(matches!(from_value::<Address>(&value), Err(Error::Decode(_))));
+
+        let value = parse_value("{\"zip\":300}").unwrap();
+        assert!(matches!(from_value::<Address>(&value), Err(Error::Decode(_))));
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::env;
+
+use crate::owned::{escape, unescape};
+use crate::{OwnedEntry, OwnedValue, Value};
+
+/// Builds an object from the environment variables starting with `prefix`.
+///
+/// The prefix and the `_` after it are stripped and the rest of the name is
+/// lowercased, so `APP_DB_HOST=localhost` with prefix `"APP"` becomes
+/// `{"db_host": "localhost"}`. All values are strings and entries are sorted by
+/// key since the environment has no order of its own. Variables whose name or
+/// value isn't valid Unicode are skipped.
+pub fn from_env_vars(prefix: &str) -> OwnedValue {
+    let prefix = format!("{}_", prefix);
+    let mut entries: Vec<OwnedEntry> = env::vars_os()
+        .filter_map(|(name, value)| {
+            let (name, value) = (name.into_string().ok()?, value.into_string().ok()?);
+            let key = name.strip_prefix(&prefix)?;
+            Some(OwnedEntry {
+                key: escape(&key.to_lowercase()),
+                value: OwnedValue::String(escape(&value)),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    return OwnedValue::Object(entries.into_boxed_slice());
+}
+
+impl<'a> Value<'a> {
+    /// Flattens an object into environment variable style pairs.
+    ///
+    /// Names are the uppercased key path joined with `_` and prefixed with
+    /// `prefix`, so `{"db": {"host": "localhost"}}` with prefix `"APP"` becomes
+    /// `APP_DB_HOST=localhost`. Strings are decoded and numbers written as is;
+    /// anything else is written as JSON.
+    pub fn to_env_vars(&self, prefix: &str) -> HashMap<String, String> {
+        let mut vars = HashMap::new();
+        collect_env_vars(self, prefix.to_uppercase(), &mut vars);
+        return vars;
+    }
+}
+
+fn collect_env_vars(value: &Value, name: String, vars: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(o) => {
+            for entry in o.iter() {
+                let key = unescape(entry.key).to_uppercase();
+                let name = if name.is_empty() { key } else { format!("{}_{}", name, key) };
+                collect_env_vars(&entry.value, name, vars);
+            }
+        }
+        Value::String(s) => {
+            vars.insert(name, unescape(s));
+        }
+        Value::Number(n) => {
+            vars.insert(name, n.to_string());
+        }
+        _ => {
+            vars.insert(name, value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::{from_env_vars, parse_value};
+
+    #[test]
+    fn to_env_vars_test() {
+        let s = "{\"db_host\":\"localhost\",\"db_port\":5432,\"debug\":false,\"hosts\":[\"a\",\"b\"],\"cache\":{\"ttl\":60}}";
+        let vars = parse_value(s).unwrap().to_env_vars("APP");
+
+        assert_eq!(vars.len(), 5);
+        assert_eq!(vars["APP_DB_HOST"], "localhost");
+        assert_eq!(vars["APP_DB_PORT"], "5432");
+        assert_eq!(vars["APP_DEBUG"], "false");
+        assert_eq!(vars["APP_HOSTS"], "[\"a\",\"b\"]");
+        assert_eq!(vars["APP_CACHE_TTL"], "60");
+
+        let vars = parse_value("{\"a\":1}").unwrap().to_env_vars("");
+        assert_eq!(vars["A"], "1");
+    }
+
+    #[test]
+    fn env_vars_round_trip_test() {
+        let value = parse_value("{\"text\":\"a\\nb \\\"c\\\" caf\\u00e9\"}").unwrap();
+        let vars = value.to_env_vars("JSTR_ROUND_TRIP");
+        assert_eq!(vars["JSTR_ROUND_TRIP_TEXT"], "a\nb \"c\" caf\u{e9}");
+
+        for (name, value) in vars.iter() {
+            env::set_var(name, value);
+        }
+        let expected = parse_value("{\"text\":\"a\\nb \\\"c\\\" caf\u{e9}\"}").unwrap();
+        assert_eq!(from_env_vars("JSTR_ROUND_TRIP"), expected.to_owned());
+    }
+
+    #[test]
+    fn from_env_vars_test() {
+        env::set_var("JSTR_TEST_DB_HOST", "localhost");
+        env::set_var("JSTR_TEST_DB_PORT", "5432");
+        env::set_var("JSTR_TEST_NAME", "a \"quoted\" name");
+
+        #[cfg(unix)]
+        {
+            use std::ffi::OsStr;
+            use std::os::unix::ffi::OsStrExt;
+            env::set_var("JSTR_TEST_BAD", OsStr::from_bytes(b"\xff\xfe"));
+        }
+
+        let value = from_env_vars("JSTR_TEST");
+        let expected = parse_value("{\"db_host\":\"localhost\",\"db_port\":\"5432\",\"name\":\"a \\\"quoted\\\" name\"}").unwrap();
+        assert_eq!(value, expected.to_owned());
+    }
+}
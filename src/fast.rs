@@ -0,0 +1,146 @@
+use std::str;
+
+use crate::{Error, OwnedEntry, OwnedValue};
+
+/// Parses a JSON value straight from bytes.
+///
+/// Accepts the same input as `parse_value`, but works on bytes instead of
+/// chars: structural characters are found with plain byte comparisons and
+/// only the contents of strings and keys are checked for valid UTF-8, as they
+/// are copied out. Invalid UTF-8 is reported as `Error::InvalidUtf8` with the
+/// offset where the offending string starts.
+pub fn parse_fast(s: &[u8]) -> Result<OwnedValue, Error> {
+    let mut i = skip_whitespace(s, 0);
+    let (value, end) = value(s, i)?;
+    i = skip_whitespace(s, end);
+    if i < s.len() {
+        return Err(bad_byte(s, i));
+    }
+    return Ok(value);
+}
+
+fn bad_byte(s: &[u8], i: usize) -> Error {
+    if i >= s.len() {
+        return Error::EarlyEnd;
+    }
+    for end in i + 1..=s.len().min(i + 4) {
+        if let Ok(c) = str::from_utf8(&s[i..end]) {
+            return Error::BadChar(c.chars().next().unwrap_or('\u{fffd}'), i);
+        }
+    }
+    return Error::InvalidUtf8(i);
+}
+
+fn skip_whitespace(s: &[u8], mut i: usize) -> usize {
+    while i < s.len() {
+        match s[i] {
+            b' ' | b'\n' | b',' | b':' => i += 1,
+            _ => break,
+        }
+    }
+    return i;
+}
+
+fn value(s: &[u8], i: usize) -> Result<(OwnedValue, usize), Error> {
+    let b = *s.get(i).ok_or(Error::EarlyEnd)?;
+    return match b {
+        b'"' => {
+            let (string, end) = string(s, i)?;
+            Ok((OwnedValue::String(string), end))
+        }
+        b'{' => object(s, i),
+        b'[' => array(s, i),
+        b'-' | b'0'..=b'9' => {
+            let mut end = i + 1;
+            while end < s.len() && matches!(s[end], b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') {
+                end += 1;
+            }
+            // Everything matched above is ASCII.
+            let n = str::from_utf8(&s[i..end]).map_err(|_| Error::InvalidUtf8(i))?;
+            Ok((OwnedValue::Number(n.to_string()), end))
+        }
+        _ => literal(s, i),
+    };
+}
+
+fn literal(s: &[u8], i: usize) -> Result<(OwnedValue, usize), Error> {
+    let rest = &s[i..];
+    if rest.starts_with(b"true") {
+        return Ok((OwnedValue::Boolean("true".to_string()), i + 4));
+    }
+    if rest.starts_with(b"false") {
+        return Ok((OwnedValue::Boolean("false".to_string()), i + 5));
+    }
+    if rest.starts_with(b"null") {
+        return Ok((OwnedValue::Null, i + 4));
+    }
+    return Err(bad_byte(s, i));
+}
+
+/// Reads the string starting at the quote at `i`, returning its contents with
+/// escapes kept and the offset just past the closing quote.
+fn string(s: &[u8], i: usize) -> Result<(String, usize), Error> {
+    let mut end = i + 1;
+    let mut esc = false;
+    while end < s.len() {
+        match s[end] {
+            _ if esc => esc = false,
+            b'\\' => esc = true,
+            b'"' => {
+                let contents = str::from_utf8(&s[i + 1..end]).map_err(|_| Error::InvalidUtf8(i))?;
+                return Ok((contents.to_string(), end + 1));
+            }
+            _ => {}
+        }
+        end += 1;
+    }
+    return Err(Error::NoEnd);
+}
+
+fn object(s: &[u8], i: usize) -> Result<(OwnedValue, usize), Error> {
+    let mut entries = vec![];
+    let mut i = skip_whitespace(s, i + 1);
+    while *s.get(i).ok_or(Error::EarlyEnd)? != b'}' {
+        if s[i] != b'"' {
+            return Err(bad_byte(s, i));
+        }
+        let (key, end) = string(s, i)?;
+        let (value, end) = value(s, skip_whitespace(s, end))?;
+        entries.push(OwnedEntry { key, value });
+        i = skip_whitespace(s, end);
+    }
+    return Ok((OwnedValue::Object(entries.into_boxed_slice()), i + 1));
+}
+
+fn array(s: &[u8], i: usize) -> Result<(OwnedValue, usize), Error> {
+    let mut values = vec![];
+    let mut i = skip_whitespace(s, i + 1);
+    while *s.get(i).ok_or(Error::EarlyEnd)? != b']' {
+        let (value, end) = value(s, i)?;
+        values.push(value);
+        i = skip_whitespace(s, end);
+    }
+    return Ok((OwnedValue::Array(values.into_boxed_slice()), i + 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_fast, parse_value, Error};
+
+    #[test]
+    fn parse_fast_test() {
+        let s = "{\"a\": [1, -2.5e3, true, false, null], \"b\u{e9}\": {\"c\": \"d \\\"\u{1f600}\\\"\"}, \"e\": []}";
+        assert_eq!(parse_fast(s.as_bytes()).unwrap(), parse_value(s).unwrap().to_owned());
+        assert_eq!(parse_fast(b" 42 ").unwrap(), parse_value("42").unwrap().to_owned());
+    }
+
+    #[test]
+    fn parse_fast_error_test() {
+        assert!(matches!(parse_fast(b"[\"a\xff\"]"), Err(Error::InvalidUtf8(1))));
+        assert!(matches!(parse_fast(b"[1] x"), Err(Error::BadChar('x', 4))));
+        assert!(matches!(parse_fast("[\u{e9}]".as_bytes()), Err(Error::BadChar('\u{e9}', 1))));
+        assert!(matches!(parse_fast(b"{\"a\":1"), Err(Error::EarlyEnd)));
+        assert!(matches!(parse_fast(b"[\"a"), Err(Error::NoEnd)));
+        assert!(matches!(parse_fast(b"[nul]"), Err(Error::BadChar('n', 1))));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{Error, Value};
+
+impl<'a> Value<'a> {
+    /// Writes the value as a GraphQL input value, e.g. `{id: 1, tags: ["a"]}`.
+    ///
+    /// Object keys are written bare, as GraphQL expects field names to be.
+    /// Everything else is written as in JSON. Fails with `Error::Decode` if a
+    /// key isn't a GraphQL name.
+    pub fn to_graphql_input_object(&self) -> Result<String, Error> {
+        return match self {
+            Value::Object(o) => {
+                let mut fields = Vec::with_capacity(o.len());
+                for e in o.iter() {
+                    if !is_name(e.key) {
+                        return Err(Error::Decode(format!("{:?} isn't a GraphQL name", e.key)));
+                    }
+                    fields.push(format!("{}: {}", e.key, e.value.to_graphql_input_object()?));
+                }
+                Ok(format!("{{{}}}", fields.join(", ")))
+            }
+            Value::Array(a) => {
+                let values = a.iter().map(Value::to_graphql_input_object).collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("[{}]", values.join(", ")))
+            }
+            _ => Ok(self.to_string()),
+        };
+    }
+}
+
+/// Whether `key` is a GraphQL `Name`: a letter or underscore followed by
+/// letters, digits and underscores.
+fn is_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    return match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        _ => false,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Error};
+
+    #[test]
+    fn to_graphql_input_object_test() {
+        let value = parse_value("{\"name\":\"a \\\"b\\\"\",\"age\":30,\"admin\":false,\"manager\":null,\"address\":{\"city\":\"x\",\"zip\":[1,2]},\"tags\":[],\"_id2\":1}").unwrap();
+        assert_eq!(
+            value.to_graphql_input_object().unwrap(),
+            "{name: \"a \\\"b\\\"\", age: 30, admin: false, manager: null, address: {city: \"x\", zip: [1, 2]}, tags: [], _id2: 1}"
+        );
+        assert_eq!(parse_value("{}").unwrap().to_graphql_input_object().unwrap(), "{}");
+
+        let rejected = ["{\"a: 1}) { secret } #\":1}", "{\"\":1}", "{\"1a\":1}", "{\"a-b\":1}", "{\"a\":[{\"b c\":1}]}", "{\"a\":{\"\\u00e9\":1}}"];
+        for s in rejected.iter() {
+            assert!(matches!(parse_value(s).unwrap().to_graphql_input_object(), Err(Error::Decode(_))), "accepted {}", s);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::owned::unescape;
+use crate::Value;
+
+impl<'a> Value<'a> {
+    /// Writes a flat object as HTML attributes, e.g. `class="a" disabled`.
+    ///
+    /// `true` gives just the attribute name, while `false` and `null` leave
+    /// the attribute out. Other values are HTML escaped and double quoted,
+    /// with strings decoded first and nested objects and arrays written as
+    /// compact JSON. Keys that aren't valid attribute names (empty, or
+    /// containing whitespace, control characters, quotes, `>`, `/` or `=`)
+    /// are skipped. A value that isn't an object gives an empty string.
+    pub fn to_html_attributes(&self) -> String {
+        let o = match self {
+            Value::Object(o) => o,
+            _ => return String::new(),
+        };
+
+        let attributes: Vec<String> = o
+            .iter()
+            .filter_map(|e| {
+                let name = unescape(e.key);
+                if !is_attribute_name(&name) {
+                    return None;
+                }
+                let name = html_escape(&name);
+                let text = match &e.value {
+                    Value::Boolean(b) if *b == "true" => return Some(name),
+                    Value::Boolean(_) | Value::Null => return None,
+                    Value::String(s) => unescape(s),
+                    other => other.to_string(),
+                };
+                Some(format!("{}=\"{}\"", name, html_escape(&text)))
+            })
+            .collect();
+        return attributes.join(" ");
+    }
+}
+
+fn is_attribute_name(s: &str) -> bool {
+    return !s.is_empty()
+        && !s.chars().any(|c| c.is_whitespace() || c.is_control() || ['"', '\'', '>', '/', '='].contains(&c));
+}
+
+fn html_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_value;
+
+    #[test]
+    fn to_html_attributes_test() {
+        let value = parse_value("{\"class\":\"foo\",\"data-id\":1,\"disabled\":true,\"hidden\":false,\"title\":null,\"alt\":\"a \\\"b\\\" & <c>\",\"data-x\":[1,\"y\"]}").unwrap();
+        assert_eq!(
+            value.to_html_attributes(),
+            "class=\"foo\" data-id=\"1\" disabled alt=\"a &quot;b&quot; &amp; &lt;c&gt;\" data-x=\"[1,&quot;y&quot;]\""
+        );
+        assert_eq!(
+            parse_value("{\"x onclick=alert(1)\":\"y\",\"a\\\"b\":1,\"c'd\":1,\"e>f\":1,\"g/h\":1,\"i=j\":1,\"\":1,\"ok\":2}")
+                .unwrap()
+                .to_html_attributes(),
+            "ok=\"2\""
+        );
+        assert_eq!(parse_value("{}").unwrap().to_html_attributes(), "");
+        assert_eq!(parse_value("[1]").unwrap().to_html_attributes(), "");
+    }
+}
@@ -0,0 +1,128 @@
+use crate::owned::unescape;
+use crate::{Error, Value};
+
+impl<'a> Value<'a> {
+    /// Writes an object as an INI file.
+    ///
+    /// Top level objects become `[section]`s and everything else a
+    /// `key = value` line, with the top level lines first since INI has no
+    /// way back out of a section. Strings are decoded and unquoted, `null` is
+    /// an empty value and arrays of scalars are joined with commas. Fails with
+    /// `Error::Decode` for anything else: values that aren't objects, objects
+    /// within sections, arrays holding objects or arrays, and names or values
+    /// that would read back differently, such as keys with `=` or comment
+    /// characters, section names with `]`, array elements with commas, or
+    /// anything with line breaks.
+    pub fn to_ini_string(&self) -> Result<String, Error> {
+        let o = match self {
+            Value::Object(o) => o,
+            other => return Err(Error::Decode(format!("an INI file can't hold a top level {}", other.type_name()))),
+        };
+
+        let mut out = String::new();
+        for entry in o.iter().filter(|e| !matches!(e.value, Value::Object(_))) {
+            write_line(entry.key, &entry.value, &mut out)?;
+        }
+        for entry in o.iter() {
+            if let Value::Object(section) = &entry.value {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                let name = unescape(entry.key);
+                if !is_section_name(&name) {
+                    return Err(Error::Decode(format!("{:?} isn't an INI section name", name)));
+                }
+                out.push_str(&format!("[{}]\n", name));
+                for e in section.iter() {
+                    write_line(e.key, &e.value, &mut out)?;
+                }
+            }
+        }
+        return Ok(out);
+    }
+}
+
+fn write_line(key: &str, value: &Value, out: &mut String) -> Result<(), Error> {
+    let key = unescape(key);
+    if !is_key(&key) {
+        return Err(Error::Decode(format!("{:?} isn't an INI key", key)));
+    }
+    let text = match value {
+        Value::Array(a) => {
+            let elements = a.iter().map(ini_scalar).collect::<Result<Vec<_>, _>>()?;
+            if let Some(e) = elements.iter().find(|e| e.contains(',')) {
+                return Err(Error::Decode(format!("an INI list element can't hold a comma: {:?}", e)));
+            }
+            elements.join(",")
+        }
+        other => ini_scalar(other)?,
+    };
+    out.push_str(&format!("{} = {}\n", key, text));
+    return Ok(());
+}
+
+/// Whether `key` reads back as the same key: not empty, no surrounding
+/// whitespace or line breaks, no `=`, `;` or `#`, and not starting with `[`.
+fn is_key(key: &str) -> bool {
+    return !key.is_empty()
+        && key.trim() == key
+        && !key.starts_with('[')
+        && !key.chars().any(|c| c.is_control() || ['=', ';', '#'].contains(&c));
+}
+
+/// Whether `name` can go between `[` and `]`.
+fn is_section_name(name: &str) -> bool {
+    return !name.is_empty() && name.trim() == name && !name.chars().any(|c| c.is_control() || c == ']');
+}
+
+fn ini_scalar(value: &Value) -> Result<String, Error> {
+    return match value {
+        Value::Null => Ok(String::new()),
+        Value::String(s) => {
+            let text = unescape(s);
+            if text.trim() != text || text.chars().any(|c| c.is_control()) {
+                return Err(Error::Decode(format!("an INI value can't hold {:?}", text)));
+            }
+            Ok(text)
+        }
+        Value::Boolean(text) | Value::Number(text) => Ok(text.to_string()),
+        other => Err(Error::Decode(format!("an INI value can't hold a nested {}", other.type_name()))),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Error};
+
+    #[test]
+    fn to_ini_string_test() {
+        let value = parse_value("{\"name\":\"app\",\"database\":{\"host\":\"localhost\",\"port\":5432,\"replicas\":[\"a\",\"b\"]},\"debug\":true,\"log\":{\"level\":\"info\",\"file\":null}}").unwrap();
+        let expected = "name = app\ndebug = true\n\n[database]\nhost = localhost\nport = 5432\nreplicas = a,b\n\n[log]\nlevel = info\nfile = \n";
+        assert_eq!(value.to_ini_string().unwrap(), expected);
+
+        let value = parse_value("{\"a\":{\"b\":1}}").unwrap();
+        assert_eq!(value.to_ini_string().unwrap(), "[a]\nb = 1\n");
+
+        let rejected = [
+            "{\"a\":{\"b\":{\"c\":1}}}",
+            "{\"a\":[{\"b\":1}]}",
+            "{\"a\":[[1]]}",
+            "[1]",
+            "{\"a\":\"x\\n[admin]\\nroot = true\"}",
+            "{\"a\":\" padded \"}",
+            "{\"a=b\":1}",
+            "{\"a;b\":1}",
+            "{\"#a\":1}",
+            "{\"[a]\":1}",
+            "{\"a\\nb\":1}",
+            "{\"\":1}",
+            "{\"a]b\":{\"c\":1}}",
+            "{\"a\\nb\":{\"c\":1}}",
+            "{\"s\":{\"a=b\":1}}",
+            "{\"a\":[\"x,y\"]}",
+        ];
+        for s in rejected.iter() {
+            assert!(matches!(parse_value(s).unwrap().to_ini_string(), Err(Error::Decode(_))), "accepted {}", s);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::options::{parse_with_options, ParseOptions};
+use crate::{Error, OwnedValue, Value};
+
+/// Parses a JSON5 document: unquoted keys, single quoted strings, trailing
+/// commas, comments, `NaN` and `Infinity` (which become `null`), hexadecimal
+/// numbers, a leading `+` and the extra JavaScript string escapes. Numbers
+/// and strings are converted to standard JSON, and a repeated key keeps its
+/// last value, as in JavaScript.
+pub fn parse_json5(s: &str) -> Result<OwnedValue, Error> {
+    return parse_with_options(s, &ParseOptions::permissive());
+}
+
+impl<'a> Value<'a> {
+    /// Writes the value as indented JSON5.
+    ///
+    /// Keys that are valid identifiers are left unquoted and every member is
+    /// followed by a comma, trailing ones included. Strings stay double quoted.
+    pub fn to_json5_string(&self) -> String {
+        let mut out = String::new();
+        write_json5(self, 0, &mut out);
+        return out;
+    }
+}
+
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    return match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        }
+        _ => false,
+    };
+}
+
+fn indent(depth: usize, out: &mut String) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+fn write_json5(value: &Value, depth: usize, out: &mut String) {
+    match value {
+        Value::Object(o) if o.is_empty() => out.push_str("{}"),
+        Value::Object(o) => {
+            out.push_str("{\n");
+            for entry in o.iter() {
+                indent(depth + 1, out);
+                if is_identifier(entry.key) {
+                    out.push_str(entry.key);
+                } else {
+                    out.push('"');
+                    out.push_str(entry.key);
+                    out.push('"');
+                }
+                out.push_str(": ");
+                write_json5(&entry.value, depth + 1, out);
+                out.push_str(",\n");
+            }
+            indent(depth, out);
+            out.push('}');
+        }
+        Value::Array(a) if a.is_empty() => out.push_str("[]"),
+        Value::Array(a) => {
+            out.push_str("[\n");
+            for value in a.iter() {
+                indent(depth + 1, out);
+                write_json5(value, depth + 1, out);
+                out.push_str(",\n");
+            }
+            indent(depth, out);
+            out.push(']');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_json5, parse_value, OwnedValue};
+
+    #[test]
+    fn to_json5_string_test() {
+        let value = parse_value("{\"name\":\"abcd\",\"$id\":1,\"not-ident\":{},\"tags\":[1.5,true],\"1st\":[]}").unwrap();
+        let expected = "{\n  name: \"abcd\",\n  $id: 1,\n  \"not-ident\": {},\n  tags: [\n    1.5,\n    true,\n  ],\n  \"1st\": [],\n}";
+        assert_eq!(value.to_json5_string(), expected);
+
+        let value = parse_value("{\"a-b\":[1,{\"c d\":\"x\"}]}").unwrap();
+        assert_eq!(parse_value(&value.to_json5_string()).unwrap(), value);
+    }
+
+    #[test]
+    fn parse_json5_test() {
+        // The example from json5.org.
+        let example = r#"{
+  // comments
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \
+No \\n's!",
+  hexadecimal: 0xdecaf,
+  leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+  positiveSign: +1,
+  trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON",
+}
+"#;
+        let expected = r#"{"unquoted":"and you can quote me on that","singleQuotes":"I can use \"double quotes\" here","lineBreaks":"Look, Mom! No \\n's!","hexadecimal":912559,"leadingDecimalPoint":0.8675309,"andTrailing":8675309,"positiveSign":1,"trailingComma":"in objects","andIn":["arrays"],"backwardsCompatible":"with JSON"}"#;
+        assert_eq!(parse_json5(example).unwrap(), parse_value(expected).unwrap().to_owned());
+
+        // The json5 package's own package.json5, abridged.
+        let package = r#"// This is a generated file. Do not edit.
+{
+  name: 'json5',
+  version: '2.2.3',
+  description: 'JSON for Humans',
+  main: 'lib/index.js',
+  bin: 'lib/cli.js',
+  files: [
+    'lib/',
+    'dist/',
+  ],
+  engines: {
+    node: '>=6',
+  },
+  scripts: {
+    build: 'rollup -c',
+    lint: 'eslint --fix .',
+    test: 'tap -Rspec --100 test',
+  },
+  repository: {
+    type: 'git',
+    url: 'git+https://github.com/json5/json5.git',
+  },
+  keywords: [
+    'json',
+    'json5',
+    'es5',
+    'es2015',
+    'ecmascript',
+  ],
+  license: 'MIT',
+}
+"#;
+        let value = parse_json5(package).unwrap();
+        assert_eq!(value.get("name"), Some(&OwnedValue::String("json5".to_string())));
+        assert_eq!(value.get("engines").and_then(|e| e.get("node")), Some(&OwnedValue::String(">=6".to_string())));
+        match value.get("keywords") {
+            Some(OwnedValue::Array(a)) => assert_eq!(a.len(), 5),
+            other => panic!("expected an array, got {:?}", other),
+        }
+
+        let numbers = parse_json5("[0x1F, -0XA, +.5, -5., 1e3, +Infinity, -NaN]").unwrap();
+        assert_eq!(numbers, parse_value("[31,-10,0.5,-5,1e3,null,null]").unwrap().to_owned());
+
+        let escapes = parse_json5(r#"['\x41\v\0', "it\'s", 'tab	here', "\q", café]"#);
+        assert!(escapes.is_err());
+        let escapes = parse_json5(r#"['\x41\v\0', "it\'s", 'tab	here', "\q", {café: 1}]"#).unwrap();
+        assert_eq!(escapes, parse_value(r#"["A\u000b\u0000","it's","tab\there","q",{"café":1}]"#).unwrap().to_owned());
+
+        assert!(parse_json5("[0x]").is_err());
+        assert!(parse_json5("[.]").is_err());
+        assert!(parse_json5("['\\1']").is_err());
+        assert!(parse_json5("['a\nb']").is_err());
+        assert!(parse_json5("[01]").is_err());
+    }
+}
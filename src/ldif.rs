@@ -0,0 +1,109 @@
+use crate::base64;
+use crate::owned::unescape;
+use crate::{Error, Value};
+
+impl<'a> Value<'a> {
+    /// Writes an object as an LDIF record, one `key: value` line per
+    /// attribute and one line per element for arrays. The `dn` attribute, if
+    /// there is one, comes first as LDIF requires. Other attributes keep
+    /// their order.
+    ///
+    /// Strings are decoded. Values that LDIF can't hold as plain text, such
+    /// as ones with line breaks, leading spaces or non-ASCII characters, are
+    /// base64 encoded with `key:: value`. Booleans are written as `TRUE` and
+    /// `FALSE`, as LDAP spells them.
+    ///
+    /// Fails with `Error::Decode` for anything else: values that aren't
+    /// objects, keys that aren't attribute names, and nulls, objects or
+    /// nested arrays as attribute values.
+    pub fn to_ldif_string(&self) -> Result<String, Error> {
+        let o = match self {
+            Value::Object(o) => o,
+            other => return Err(Error::Decode(format!("an LDIF record can't be a {}", other.type_name()))),
+        };
+
+        let mut out = String::new();
+        let dn = o.iter().filter(|e| e.key == "dn");
+        for entry in dn.chain(o.iter().filter(|e| e.key != "dn")) {
+            if !is_attribute_name(entry.key) {
+                return Err(Error::Decode(format!("{:?} isn't an LDIF attribute name", entry.key)));
+            }
+            match &entry.value {
+                Value::Array(a) => {
+                    for value in a.iter() {
+                        write_attribute(entry.key, value, &mut out)?;
+                    }
+                }
+                other => write_attribute(entry.key, other, &mut out)?,
+            }
+        }
+        return Ok(out);
+    }
+}
+
+/// Whether `key` is an attribute name: a letter followed by letters, digits
+/// and hyphens, with `;` options allowed after it.
+fn is_attribute_name(key: &str) -> bool {
+    return key.split(';').all(|part| {
+        let mut chars = part.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '-'),
+            _ => false,
+        }
+    });
+}
+
+fn write_attribute(key: &str, value: &Value, out: &mut String) -> Result<(), Error> {
+    let text = match value {
+        Value::String(s) => unescape(s),
+        Value::Number(n) => n.to_string(),
+        Value::Boolean("true") => "TRUE".to_string(),
+        Value::Boolean(_) => "FALSE".to_string(),
+        other => return Err(Error::Decode(format!("an LDIF attribute can't hold a {}", other.type_name()))),
+    };
+
+    if is_safe_string(&text) {
+        out.push_str(&format!("{}: {}\n", key, text));
+    } else {
+        out.push_str(&format!("{}:: {}\n", key, base64::encode(text.as_bytes())));
+    }
+    return Ok(());
+}
+
+/// Whether `text` is an RFC 2849 `SAFE-STRING`, which can be written as it
+/// is.
+fn is_safe_string(text: &str) -> bool {
+    if text.starts_with(&[' ', ':', '<'][..]) || text.ends_with(' ') {
+        return false;
+    }
+    return text.chars().all(|c| c.is_ascii() && c != '\0' && c != '\n' && c != '\r');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Error};
+
+    #[test]
+    fn to_ldif_string_test() {
+        let user = parse_value("{\"objectClass\":[\"top\",\"person\",\"inetOrgPerson\"],\"dn\":\"uid=jdoe,ou=people,dc=example,dc=com\",\"uid\":\"jdoe\",\"cn\":\"Jane Doe\",\"sn\":\"Doe\",\"mail\":\"jdoe@example.com\",\"uidNumber\":1001,\"description\":\"line one\\nline two\",\"displayName;lang-fr\":\"Ren\\u00e9e\",\"shadowExpire\":false}").unwrap();
+        let expected = "dn: uid=jdoe,ou=people,dc=example,dc=com\n\
+                        objectClass: top\n\
+                        objectClass: person\n\
+                        objectClass: inetOrgPerson\n\
+                        uid: jdoe\n\
+                        cn: Jane Doe\n\
+                        sn: Doe\n\
+                        mail: jdoe@example.com\n\
+                        uidNumber: 1001\n\
+                        description:: bGluZSBvbmUKbGluZSB0d28=\n\
+                        displayName;lang-fr:: UmVuw6ll\n\
+                        shadowExpire: FALSE\n";
+        assert_eq!(user.to_ldif_string().unwrap(), expected);
+        assert_eq!(parse_value("{\"cn\":\" padded\"}").unwrap().to_ldif_string().unwrap(), "cn:: IHBhZGRlZA==\n");
+
+        let rejected = ["{\"cn\":null}", "{\"cn\":{\"a\":1}}", "{\"cn\":[[1]]}", "{\"1cn\":\"x\"}", "{\"c n\":\"x\"}", "[1]"];
+        for s in rejected.iter() {
+            assert!(matches!(parse_value(s).unwrap().to_ldif_string(), Err(Error::Decode(_))), "accepted {}", s);
+        }
+    }
+}
@@ -1,14 +1,82 @@
-#![feature(try_trait)]
+#![allow(clippy::needless_return)]
 
-use std::option::NoneError;
 use std::fmt::{Display, Formatter};
-use std::borrow::Borrow;
+use std::ops::Index;
+
+mod array;
+mod arrow;
+mod base64;
+mod builder;
+mod convert;
+#[cfg(feature = "serde")]
+mod de;
+mod env;
+mod fast;
+mod graphql;
+mod html;
+mod ini;
+mod json5;
+mod jwt;
+mod ldif;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod ndjson;
+mod object;
+mod options;
+mod owned;
+mod pointer;
+#[cfg(feature = "ron")]
+mod ron;
+mod ron_text;
+#[cfg(feature = "rand")]
+mod sample;
+mod schema;
+#[cfg(feature = "serde")]
+mod ser;
+mod sexpr;
+mod sql;
+#[cfg(feature = "toml")]
+mod toml;
+mod transform;
+mod typescript;
+
+pub use array::ArrayExt;
+pub use builder::ObjectBuilder;
+#[cfg(feature = "serde")]
+pub use de::{from_value, ObjectDeserializer, ValueDeserializer};
+pub use env::from_env_vars;
+pub use fast::parse_fast;
+pub use json5::parse_json5;
+pub use jwt::parse_jwt_payload;
+#[cfg(feature = "msgpack")]
+pub use msgpack::from_messagepack;
+pub use object::{KeyWindows, ObjectExt, OwnedObjectExt};
+pub use options::{parse_permissive, parse_relaxed_json, parse_strict, parse_with_options, ParseOptions};
+pub use owned::{OwnedArray, OwnedEntry, OwnedObject, OwnedValue};
+#[cfg(feature = "ron")]
+pub use crate::ron::from_ron;
+pub use schema::{validate_and_parse, SchemaError};
+#[cfg(feature = "serde")]
+pub use ser::{to_owned_value, ValueSerializer};
 
 #[derive(Debug)]
 pub enum Error {
     BadChar(char, usize),
     NoEnd,
     EarlyEnd,
+    NotFound,
+    /// Input in some other format couldn't be converted.
+    Decode(String),
+    /// An object repeated a key, found at the given offset.
+    DuplicateKey(String, usize),
+    /// Nesting went past the configured limit at the given offset.
+    TooDeep(usize),
+    /// A string starting at the given offset wasn't valid UTF-8.
+    InvalidUtf8(usize),
+    /// A JSON Web Token didn't have the expected structure.
+    MalformedToken(String),
+    /// A value had the second type where the first was wanted.
+    WrongType(&'static str, &'static str),
 }
 
 impl Error {
@@ -17,12 +85,25 @@ impl Error {
     }
 }
 
-impl From<std::option::NoneError> for Error {
-    fn from(_: NoneError) -> Self {
-        Error::EarlyEnd
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Error::BadChar(c, i) => write!(f, "unexpected {:?} at offset {}", c, i),
+            Error::NoEnd => write!(f, "unterminated value"),
+            Error::EarlyEnd => write!(f, "unexpected end of input"),
+            Error::NotFound => write!(f, "not found"),
+            Error::Decode(msg) => write!(f, "{}", msg),
+            Error::DuplicateKey(key, i) => write!(f, "duplicate key \"{}\" at offset {}", key, i),
+            Error::TooDeep(i) => write!(f, "nesting too deep at offset {}", i),
+            Error::InvalidUtf8(i) => write!(f, "invalid UTF-8 in string at offset {}", i),
+            Error::MalformedToken(msg) => write!(f, "malformed token: {}", msg),
+            Error::WrongType(expected, found) => write!(f, "expected {}, found {}", expected, found),
+        }
     }
 }
 
+impl std::error::Error for Error {}
+
 pub type Object<'a> = Box<[Entry<'a>]>;
 pub type Array<'a> = Box<[Value<'a>]>;
 
@@ -31,6 +112,7 @@ pub enum Value<'a> {
     Boolean(&'a str),
     String(&'a str),
     Number(&'a str),
+    Null,
     Object(Object<'a>),
     Array(Array<'a>),
 }
@@ -41,11 +123,338 @@ pub struct Entry<'a> {
     pub value: Value<'a>,
 }
 
-pub fn deserialize(s: &str) -> Result<(Object, &str), Error> {
+impl<'a> Value<'a> {
+    /// The JSON name of the value's type, e.g. `"object"` or `"number"`.
+    pub fn type_name(&self) -> &'static str {
+        return match self {
+            Value::Boolean(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Number(_) => "number",
+            Value::Null => "null",
+            Value::Object(_) => "object",
+            Value::Array(_) => "array",
+        };
+    }
+
+    /// How deeply objects and arrays nest inside the value. Scalars have a
+    /// depth of 0, and each level of object or array adds 1.
+    pub fn depth(&self) -> usize {
+        return match self {
+            Value::Object(o) => 1 + o.iter().map(|e| e.value.depth()).max().unwrap_or(0),
+            Value::Array(a) => 1 + a.iter().map(Value::depth).max().unwrap_or(0),
+            _ => 0,
+        };
+    }
+
+    /// Whether the two values have the same shape: the same types throughout,
+    /// objects with the same keys in any order and arrays of the same length.
+    /// Scalars of the same type match whatever their values.
+    pub fn compare_structure(&self, other: &Value) -> bool {
+        return match (self, other) {
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|e| match other.get(e.key) {
+                        Some(theirs) => e.value.compare_structure(theirs),
+                        None => false,
+                    })
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.compare_structure(y))
+            }
+            _ => self.type_name() == other.type_name(),
+        };
+    }
+
+    /// Calls `f` with the contents of every string value in the tree, depth
+    /// first. Object keys aren't visited.
+    pub fn visit_strings<F: FnMut(&'a str)>(&self, f: &mut F) {
+        match self {
+            Value::String(s) => f(s),
+            Value::Object(o) => o.iter().for_each(|e| e.value.visit_strings(f)),
+            Value::Array(a) => a.iter().for_each(|v| v.visit_strings(f)),
+            _ => {}
+        }
+    }
+
+    /// Collects the contents of every string value in the tree, depth first.
+    pub fn all_strings(&self) -> Vec<&'a str> {
+        let mut strings = vec![];
+        self.walk("", &mut |_, v| {
+            if let Value::String(s) = v {
+                strings.push(*s);
+            }
+        });
+        return strings;
+    }
+
+    /// Looks up `key` if the value is an object. The first matching entry wins.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        if let Value::Object(o) = self {
+            return o.iter().find(|e| e.key == key).map(|e| &e.value);
+        }
+        return None;
+    }
+
+    /// Returns the contents of a string value, escapes included.
+    pub fn as_str(&self) -> Option<&'a str> {
+        if let Value::String(s) = self {
+            return Some(s);
+        }
+        return None;
+    }
+
+    pub fn as_array(&self) -> Option<&[Value<'a>]> {
+        if let Value::Array(a) = self {
+            return Some(a);
+        }
+        return None;
+    }
+
+    /// Returns the entries of an object value.
+    pub fn as_object(&self) -> Option<&[Entry<'a>]> {
+        if let Value::Object(o) = self {
+            return Some(o);
+        }
+        return None;
+    }
+
+    /// Resolves a single JSON Pointer style segment: a key for objects, or an
+    /// index for arrays.
+    pub fn at(&self, key_or_index: &str) -> Option<&Value<'a>> {
+        return match self {
+            Value::Object(_) => self.get(key_or_index),
+            Value::Array(a) => a.get(key_or_index.parse::<usize>().ok()?),
+            _ => None,
+        };
+    }
+
+    /// Parses a number value as an `i64`. Fractions, exponents and anything out
+    /// of range give `None`.
+    pub fn as_i64(&self) -> Option<i64> {
+        if let Value::Number(n) = self {
+            return n.parse().ok();
+        }
+        return None;
+    }
+
+    /// Follows `keys` down through nested objects starting at `obj` and returns
+    /// the string found there.
+    pub fn get_nested_string(obj: &[Entry<'a>], keys: &[&str]) -> Option<&'a str> {
+        return obj.get_path(keys)?.as_str();
+    }
+
+    /// Like `get_nested_string`, but for integers.
+    pub fn get_nested_i64(obj: &[Entry<'a>], keys: &[&str]) -> Option<i64> {
+        return obj.get_path(keys)?.as_i64();
+    }
+
+    /// Returns the boolean if the value is `true` or `false`, and `None` for
+    /// everything else, including strings and numbers that look like booleans.
+    pub fn as_bool_strict(&self) -> Option<bool> {
+        return match self {
+            Value::Boolean("true") => Some(true),
+            Value::Boolean("false") => Some(false),
+            _ => None,
+        };
+    }
+}
+
+/// Free function form of `Value::type_name`.
+pub fn json_type_of(v: &Value) -> &'static str {
+    return v.type_name();
+}
+
+pub fn is_json_null(v: &Value) -> bool {
+    return matches!(v, Value::Null);
+}
+
+pub fn is_json_boolean(v: &Value) -> bool {
+    return matches!(v, Value::Boolean(_));
+}
+
+pub fn is_json_number(v: &Value) -> bool {
+    return matches!(v, Value::Number(_));
+}
+
+pub fn is_json_string(v: &Value) -> bool {
+    return matches!(v, Value::String(_));
+}
+
+pub fn is_json_object(v: &Value) -> bool {
+    return matches!(v, Value::Object(_));
+}
+
+pub fn is_json_array(v: &Value) -> bool {
+    return matches!(v, Value::Array(_));
+}
+
+impl<'a> Display for Value<'a> {
+    /// Writes the value as compact JSON.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::String(s) => write!(f, "\"{}\"", s),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Null => write!(f, "null"),
+            Value::Object(o) => {
+                write!(f, "{{")?;
+                for (i, entry) in o.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "\"{}\":{}", entry.key, entry.value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Array(a) => {
+                write!(f, "[")?;
+                for (i, value) in a.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Looks up `key` as `get` does, but panics if the value isn't an object or
+/// has no such key.
+impl<'a> Index<&str> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, key: &str) -> &Value<'a> {
+        if let Value::Object(_) = self {
+            return self.get(key).unwrap_or_else(|| panic!("no key {:?} in object", key));
+        }
+        panic!("cannot look up key {:?} in a value of type {}", key, self.type_name());
+    }
+}
+
+/// Looks up the element at `index`, panicking if the value isn't an array or
+/// is too short.
+impl<'a> Index<usize> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, index: usize) -> &Value<'a> {
+        if let Value::Array(a) = self {
+            return a
+                .get(index)
+                .unwrap_or_else(|| panic!("index {} out of bounds for array of length {}", index, a.len()));
+        }
+        panic!("cannot look up index {} in a value of type {}", index, self.type_name());
+    }
+}
+
+pub fn deserialize(s: &str) -> Result<(Object<'_>, &str), Error> {
     let s = skip_whitespace(s);
     return get_object(s);
 }
 
+/// Parses a single JSON value of any type. Anything other than whitespace after
+/// the value is an error.
+pub fn parse_value(s: &str) -> Result<Value<'_>, Error> {
+    let s = skip_whitespace(s);
+    let (value, rest) = get_value(s)?;
+    let trailing = skip_whitespace(rest);
+    if let Some(c) = trailing.chars().next() {
+        return Err(Error::new(c, s.len() - trailing.len()));
+    }
+    return Ok(value);
+}
+
+/// Parses only as much of `s` as it takes to reach the value at the RFC 6901
+/// pointer `ptr`, and returns a copy of that value.
+///
+/// Values that aren't on the way to the target are skipped over by matching
+/// brackets rather than parsed, so no tree is built for them and they aren't
+/// validated. Returns `Error::NotFound` if there is nothing at `ptr`.
+pub fn parse_value_at_path(s: &str, ptr: &str) -> Result<OwnedValue, Error> {
+    let mut cur = skip_whitespace(s);
+    for token in pointer::tokens(ptr).ok_or(Error::NotFound)? {
+        cur = seek(cur, &token)?;
+    }
+    let (value, _) = get_value(cur)?;
+    return Ok(value.to_owned());
+}
+
+/// Advances to the start of the member `token` of the object or array at the
+/// start of `s`.
+fn seek<'a>(s: &'a str, token: &str) -> Result<&'a str, Error> {
+    let c = s.chars().next().ok_or(Error::EarlyEnd)?;
+    if c != '{' && c != '[' {
+        return Err(Error::NotFound);
+    }
+    let mut cur = skip_whitespace(&s[c.len_utf8()..]);
+
+    if c == '{' {
+        while cur.chars().next().ok_or(Error::EarlyEnd)? != '}' {
+            let (key, rest) = get_key(cur)?;
+            let rest = skip_whitespace(rest);
+            if key == token {
+                return Ok(rest);
+            }
+            cur = skip_whitespace(skip_value(rest)?);
+        }
+    }
+
+    if c == '[' {
+        let index = token.parse::<usize>().map_err(|_| Error::NotFound)?;
+        let mut i = 0;
+        while cur.chars().next().ok_or(Error::EarlyEnd)? != ']' {
+            if i == index {
+                return Ok(cur);
+            }
+            cur = skip_whitespace(skip_value(cur)?);
+            i += 1;
+        }
+    }
+
+    return Err(Error::NotFound);
+}
+
+/// Skips over the value at the start of `s` without building it.
+fn skip_value(s: &str) -> Result<&str, Error> {
+    let c = s.chars().next().ok_or(Error::EarlyEnd)?;
+    if c != '{' && c != '[' {
+        let (_, rest) = get_value(s)?;
+        return Ok(rest);
+    }
+
+    let mut depth = 0;
+    let mut in_str = false;
+    let mut esc = false;
+    for (i, c) in s.char_indices() {
+        if esc {
+            esc = false;
+            continue;
+        }
+
+        match c {
+            '\\' if in_str => esc = true,
+            '"' => in_str = !in_str,
+            '{' | '[' if !in_str => depth += 1,
+            '}' | ']' if !in_str => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(&s[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    return Err(Error::NoEnd);
+}
+
+/// Runs `pre` over the input before parsing it, e.g. to strip comments. The
+/// result is owned since it can't borrow from the preprocessed text.
+pub fn parse_with_preprocessor<F: Fn(&str) -> String>(s: &str, pre: F) -> Result<OwnedValue, Error> {
+    let s = pre(s);
+    return Ok(parse_value(&s)?.to_owned());
+}
+
 fn skip_whitespace(s: &str) -> &str {
     for (i, c) in s.char_indices() {
         match c {
@@ -64,7 +473,7 @@ fn get_str(s: &str) -> Result<(&str, &str), Error> {
     let mut index: usize = 0;
     let mut valid = false;
 
-    let c = s.chars().nth(0)?;
+    let c = s.chars().next().ok_or(Error::EarlyEnd)?;
     if c != '"' {
         return Err(Error::new(c, 0));
     }
@@ -97,55 +506,59 @@ fn get_str(s: &str) -> Result<(&str, &str), Error> {
 }
 
 fn get_num(s: &str) -> Result<(&str, &str), Error> {
-    let c = s.chars().nth(0)?;
-    if !(c.is_digit(10) || c == '-') {
+    let c = s.chars().next().ok_or(Error::EarlyEnd)?;
+    if !(c.is_ascii_digit() || c == '-') {
         return Err(Error::new(c, 0));
     }
     for (i, c) in s[1..].char_indices() {
-        if !c.is_digit(10) {
+        if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-') {
             return Ok((&s[..i + 1], &s[i + 1..]));
         }
     }
-    return Err(Error::NoEnd);
+    return Ok((s, &s[s.len()..]));
 }
 
 fn get_boolean(s: &str) -> Result<(&str, &str), Error> {
-    if s.starts_with("true") {
-        return Ok((&s[..4], &s[4..]));
+    if let Some(rest) = s.strip_prefix("true") {
+        return Ok((&s[..4], rest));
     }
 
-    if s.starts_with("false") {
-        return Ok((&s[..5], &s[5..]));
+    if let Some(rest) = s.strip_prefix("false") {
+        return Ok((&s[..5], rest));
+    }
+
+    return Err(Error::BadChar(s.chars().next().unwrap(), 0))
+}
+
+fn get_null(s: &str) -> Result<(&str, &str), Error> {
+    if let Some(rest) = s.strip_prefix("null") {
+        return Ok((&s[..4], rest));
     }
 
-    return Err(Error::BadChar(s.chars().nth(0).unwrap(), 0))
+    return Err(Error::BadChar(s.chars().next().unwrap(), 0))
 }
 
-fn get_object(s: &str) -> Result<(Object, &str), Error> {
-    let mut cur_s  = &s[1..];
+fn get_object(s: &str) -> Result<(Object<'_>, &str), Error> {
+    let mut cur_s  = skip_whitespace(&s[1..]);
     let mut object = vec![];
 
-    while cur_s.chars().nth(0)? != '}' {
-        cur_s = skip_whitespace(cur_s);
+    while cur_s.chars().next().ok_or(Error::EarlyEnd)? != '}' {
         let (entry, _s) = get_entry(cur_s)?;
-        cur_s = _s;
+        cur_s = skip_whitespace(_s);
         object.push(entry);
     }
-    cur_s = skip_whitespace(cur_s);
     return Ok((Object::from(object), &cur_s[1..]));
 }
 
-fn get_array(s: &str) -> Result<(Array, &str), Error> {
-    let mut cur_s  = &s[1..];
+fn get_array(s: &str) -> Result<(Array<'_>, &str), Error> {
+    let mut cur_s  = skip_whitespace(&s[1..]);
     let mut array = vec![];
 
-    while cur_s.chars().nth(0)? != ']' {
-        cur_s = skip_whitespace(cur_s);
+    while cur_s.chars().next().ok_or(Error::EarlyEnd)? != ']' {
         let (value, _s) = get_value(cur_s)?;
-        cur_s = _s;
+        cur_s = skip_whitespace(_s);
         array.push(value);
     }
-    cur_s = skip_whitespace(cur_s);
     return Ok((Array::from(array), &cur_s[1..]));
 }
 
@@ -153,8 +566,8 @@ fn get_key(s: &str) -> Result<(&str, &str), Error> {
     return get_str(s);
 }
 
-fn get_value(s: &str) -> Result<(Value, &str), Error> {
-    let c = s.chars().nth(0)?;
+fn get_value(s: &str) -> Result<(Value<'_>, &str), Error> {
+    let c = s.chars().next().ok_or(Error::EarlyEnd)?;
 
     if c == '"' {
         let string = get_str(s)?;
@@ -164,7 +577,7 @@ fn get_value(s: &str) -> Result<(Value, &str), Error> {
         ));
     }
 
-    if c.is_digit(10) || c == '-' {
+    if c.is_ascii_digit() || c == '-' {
         let num = get_num(s)?;
         return Ok((
             Value::Number(num.0),
@@ -188,10 +601,15 @@ fn get_value(s: &str) -> Result<(Value, &str), Error> {
         return Ok((Value::Boolean(b), s))
     }
 
+    if c == 'n' {
+        let (_, s) = get_null(s)?;
+        return Ok((Value::Null, s))
+    }
+
     return Err(Error::BadChar(c, 0));
 }
 
-fn get_entry(s: &str) -> Result<(Entry, &str), Error> {
+fn get_entry(s: &str) -> Result<(Entry<'_>, &str), Error> {
     let (key, s) = get_key(s)?;
     let s = skip_whitespace(s);
     let (value, s) = get_value(s)?;
@@ -204,7 +622,10 @@ fn get_entry(s: &str) -> Result<(Entry, &str), Error> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{get_entry, get_num, skip_whitespace, Value, get_object};
+    use crate::{get_entry, get_num, skip_whitespace, Value, get_object, parse_value, parse_with_preprocessor, parse_value_at_path, Error};
+    use crate::{json_type_of, is_json_null, is_json_boolean, is_json_number, is_json_string, is_json_object, is_json_array};
+
+    const COMPLEX_JSON: &str = "{\"device_type\":\"COMPUTER\",\"product\":{\"prod_price\":0,\"prod_url\":\"https://www.landsend.com/products/girls-cardigan-sweater/id_346060?attributes\\\\u003d20746,44257,44371,45134\",\"image_url\":\"s7.landsend.com/is/image/LandsEnd/514110_A519_LF_1HV\"},\"referrer\":{\"type\":\"internal\"},\"location\":{\"countryCode\":840,\"postalCode\":\"73120\",\"metroCode\":\"650\",\"regionCode\":0,\"region\":\"ok\",\"country\":\"usa\"},\"cacheBuster\":\"1589926500852940\",\"cart\":{\"quantity\":0,\"value\":0,\"productIDs\":[]},\"new_user\":false,\"user_agent\":\"{\\\"browser\\\":\\\"CHROME8\\\",\\\"browser_version\\\":\\\"81.0.4044.138\\\",\\\"operating_system\\\":\\\"WINDOWS_10\\\",\\\"device_type\\\":\\\"COMPUTER\\\",\\\"is_mobile_device\\\":\\\"false\\\"}\",\"guid\":\"d27b7979-de44-3fad-9a91-f3cb1c8f7c7a\",\"epoch\":1589926500852940,\"time\":1589926500,\"advertiserId\":22921,\"tdid\":\"4da38f58-e197-47da-99c9-486f7d90bccc\",\"guidHash\":1516801586,\"urlPath\":\"/products/girls-cardigan-sweater/id_346060\",\"mobile\":false,\"customTag\":\"shpic\\\\u003d1\\\\u0026ga_tracking_id\\\\u003dua-37627257-1\\\\u0026dxver\\\\u003d4.0.0\\\\u0026ga_info\\\\u003d{\\\"status\\\":\\\"ok\\\",\\\"ga_tracking_id\\\":\\\"ua-37627257-1\\\",\\\"ga_client_id\\\":\\\"1245476243.1575937452\\\",\\\"shpt\\\":\\\"girls cardigan sweater | lands\\\\u0027 end\\\",\\\"execution_workflow\\\":{\\\"iteration\\\":1,\\\"gettrackingidbyga\\\":\\\"ok\\\",\\\"getclientidbytracker\\\":\\\"ok\\\",\\\"shpt\\\":\\\"ok\\\"}}\\\\u0026shadditional\\\\u003dga_tracking_id\\\\u003dua-37627257-1,shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end,ga_client_id\\\\u003d1245476243.1575937452\\\\u0026fdx\\\\u003d1\\\\u0026shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end\\\\u0026ga_client_id\\\\u003d1245476243.1575937452\",\"ip\":\"68.12.228.152\"}";
 
     #[test]
     fn skip_whitespace_test() {
@@ -220,7 +641,165 @@ mod tests {
         let (s2, i) = get_num(s).unwrap();
 
         assert_eq!(s2, "-1234");
-        assert_eq!(i, ",")
+        assert_eq!(i, ",");
+
+        let (s2, i) = get_num("1.5e-3]").unwrap();
+        assert_eq!(s2, "1.5e-3");
+        assert_eq!(i, "]");
+    }
+
+    #[test]
+    fn parse_value_test() {
+        assert_eq!(parse_value(" 42 ").unwrap(), Value::Number("42"));
+        assert_eq!(parse_value("\"abc\"").unwrap(), Value::String("abc"));
+        assert!(parse_value("[1] 2").is_err());
+    }
+
+    #[test]
+    fn parse_value_at_path_test() {
+        let s = "{\"a\":{\"skip\":[1,{\"x\":\"]}\"}],\"b\":[0,{\"c\":{\"d\":\"found\"}}]},\"broken\":[}";
+        let value = parse_value_at_path(s, "/a/b/1/c").unwrap();
+        assert_eq!(value, parse_value("{\"d\":\"found\"}").unwrap().to_owned());
+
+        assert!(matches!(parse_value_at_path(s, "/a/b/2"), Err(Error::NotFound)));
+        assert!(matches!(parse_value_at_path(s, "/a/x"), Err(Error::NotFound)));
+        assert!(matches!(parse_value_at_path("{\"a\":\"\u{e9}\"}", "/a/x"), Err(Error::NotFound)));
+        assert!(matches!(parse_value_at_path("{\"a\":\u{e9}}", "/a/x"), Err(Error::NotFound)));
+        assert!(matches!(parse_value_at_path("\u{e9}", "/0"), Err(Error::NotFound)));
+        assert!(parse_value(s).is_err());
+    }
+
+    #[test]
+    fn parse_with_preprocessor_test() {
+        let s = "{\n  // the user\n  \"name\": \"abcd\", // trailing\n  \"id\": 12\n}\n";
+        assert!(parse_value(s).is_err());
+
+        let strip_comments = |s: &str| {
+            s.lines()
+                .map(|line| match line.find("//") {
+                    Some(i) => &line[..i],
+                    None => line,
+                })
+                .collect::<Vec<&str>>()
+                .join("\n")
+        };
+        let value = parse_with_preprocessor(s, strip_comments).unwrap();
+        assert_eq!(value, parse_value("{\"name\":\"abcd\",\"id\":12}").unwrap().to_owned());
+    }
+
+    #[test]
+    fn json_type_of_test() {
+        let value = parse_value("[null,true,1,\"a\",{},[]]").unwrap();
+        let types: Vec<&str> = match &value {
+            Value::Array(a) => a.iter().map(json_type_of).collect(),
+            _ => panic!("expected an array"),
+        };
+        assert_eq!(types, vec!["null", "boolean", "number", "string", "object", "array"]);
+
+        let checks: [fn(&Value) -> bool; 6] = [is_json_null, is_json_boolean, is_json_number, is_json_string, is_json_object, is_json_array];
+        if let Value::Array(a) = &value {
+            for (i, check) in checks.iter().enumerate() {
+                let matched: Vec<bool> = a.iter().map(|v| check(v)).collect();
+                assert_eq!(matched.iter().filter(|m| **m).count(), 1);
+                assert!(matched[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn at_test() {
+        let value = parse_value("{\"a\":[1,2],\"0\":true}").unwrap();
+        assert_eq!(value.at("0"), Some(&Value::Boolean("true")));
+        assert_eq!(value.at("b"), None);
+
+        let a = value.at("a").unwrap();
+        assert_eq!(a.as_array().map(|a| a.len()), Some(2));
+        assert_eq!(value.as_object().map(|o| o.len()), Some(2));
+        assert_eq!(a.as_object(), None);
+        assert_eq!(a.at("1"), Some(&Value::Number("2")));
+        assert_eq!(a.at("2"), None);
+        assert_eq!(a.at("x"), None);
+        assert_eq!(Value::Number("1").at("0"), None);
+    }
+
+    #[test]
+    fn visit_strings_test() {
+        let value = parse_value(COMPLEX_JSON).unwrap();
+        let mut strings = vec![];
+        value.visit_strings(&mut |s| strings.push(s));
+
+        assert_eq!(strings.len(), 15);
+        assert_eq!(strings[0], "COMPUTER");
+        assert_eq!(strings, value.all_strings());
+    }
+
+    #[test]
+    fn compare_structure_test() {
+        let a = parse_value("{\"a\":1,\"b\":[\"x\",{\"c\":true}],\"d\":null}").unwrap();
+        let same = ["{\"a\":2,\"b\":[\"y\",{\"c\":false}],\"d\":null}", "{\"d\":null,\"b\":[\"\",{\"c\":true}],\"a\":-1.5}"];
+        for s in same.iter() {
+            assert!(a.compare_structure(&parse_value(s).unwrap()), "{}", s);
+        }
+
+        let different = [
+            "{\"a\":\"1\",\"b\":[\"x\",{\"c\":true}],\"d\":null}",
+            "{\"a\":1,\"b\":[\"x\"],\"d\":null}",
+            "{\"a\":1,\"b\":[\"x\",{\"e\":true}],\"d\":null}",
+            "{\"a\":1,\"b\":[\"x\",{\"c\":true}]}",
+            "{\"a\":1,\"b\":[\"x\",{\"c\":true}],\"d\":null,\"e\":1}",
+            "[1]",
+        ];
+        for s in different.iter() {
+            assert!(!a.compare_structure(&parse_value(s).unwrap()), "{}", s);
+        }
+    }
+
+    #[test]
+    fn index_test() {
+        let value = parse_value("{\"name\":\"a\",\"tags\":[1,{\"b\":true}]}").unwrap();
+        assert_eq!(value["name"], Value::String("a"));
+        assert_eq!(value["tags"][0], Value::Number("1"));
+        assert_eq!(value["tags"][1]["b"], Value::Boolean("true"));
+    }
+
+    #[test]
+    #[should_panic(expected = "no key \"missing\" in object")]
+    fn index_missing_key_test() {
+        let value = parse_value("{\"name\":\"a\"}").unwrap();
+        let _ = &value["missing"];
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 out of bounds for array of length 2")]
+    fn index_out_of_bounds_test() {
+        let value = parse_value("[1,2]").unwrap();
+        let _ = &value[2];
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot look up key \"a\" in a value of type array")]
+    fn index_wrong_type_test() {
+        let value = parse_value("[1,2]").unwrap();
+        let _ = &value["a"];
+    }
+
+    #[test]
+    fn null_test() {
+        let value = parse_value("[null,1]").unwrap();
+        assert_eq!(value, Value::Array(Box::new([Value::Null, Value::Number("1")])));
+        assert_eq!(value.to_string(), "[null,1]");
+        assert!(parse_value("nul").is_err());
+    }
+
+    #[test]
+    fn as_bool_strict_test() {
+        assert_eq!(parse_value("true").unwrap().as_bool_strict(), Some(true));
+        assert_eq!(parse_value("false").unwrap().as_bool_strict(), Some(false));
+        assert_eq!(parse_value("\"true\"").unwrap().as_bool_strict(), None);
+        assert_eq!(parse_value("1").unwrap().as_bool_strict(), None);
+        assert_eq!(parse_value("null").unwrap().as_bool_strict(), None);
+        assert_eq!(parse_value("{}").unwrap().as_bool_strict(), None);
+        assert_eq!(parse_value("[true]").unwrap().as_bool_strict(), None);
     }
 
     #[test]
@@ -254,9 +833,12 @@ mod tests {
 
     #[test]
     fn complex_test() {
-        let json = "{\"device_type\":\"COMPUTER\",\"product\":{\"prod_price\":0,\"prod_url\":\"https://www.landsend.com/products/girls-cardigan-sweater/id_346060?attributes\\\\u003d20746,44257,44371,45134\",\"image_url\":\"s7.landsend.com/is/image/LandsEnd/514110_A519_LF_1HV\"},\"referrer\":{\"type\":\"internal\"},\"location\":{\"countryCode\":840,\"postalCode\":\"73120\",\"metroCode\":\"650\",\"regionCode\":0,\"region\":\"ok\",\"country\":\"usa\"},\"cacheBuster\":\"1589926500852940\",\"cart\":{\"quantity\":0,\"value\":0,\"productIDs\":[]},\"new_user\":false,\"user_agent\":\"{\\\"browser\\\":\\\"CHROME8\\\",\\\"browser_version\\\":\\\"81.0.4044.138\\\",\\\"operating_system\\\":\\\"WINDOWS_10\\\",\\\"device_type\\\":\\\"COMPUTER\\\",\\\"is_mobile_device\\\":\\\"false\\\"}\",\"guid\":\"d27b7979-de44-3fad-9a91-f3cb1c8f7c7a\",\"epoch\":1589926500852940,\"time\":1589926500,\"advertiserId\":22921,\"tdid\":\"4da38f58-e197-47da-99c9-486f7d90bccc\",\"guidHash\":1516801586,\"urlPath\":\"/products/girls-cardigan-sweater/id_346060\",\"mobile\":false,\"customTag\":\"shpic\\\\u003d1\\\\u0026ga_tracking_id\\\\u003dua-37627257-1\\\\u0026dxver\\\\u003d4.0.0\\\\u0026ga_info\\\\u003d{\\\"status\\\":\\\"ok\\\",\\\"ga_tracking_id\\\":\\\"ua-37627257-1\\\",\\\"ga_client_id\\\":\\\"1245476243.1575937452\\\",\\\"shpt\\\":\\\"girls cardigan sweater | lands\\\\u0027 end\\\",\\\"execution_workflow\\\":{\\\"iteration\\\":1,\\\"gettrackingidbyga\\\":\\\"ok\\\",\\\"getclientidbytracker\\\":\\\"ok\\\",\\\"shpt\\\":\\\"ok\\\"}}\\\\u0026shadditional\\\\u003dga_tracking_id\\\\u003dua-37627257-1,shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end,ga_client_id\\\\u003d1245476243.1575937452\\\\u0026fdx\\\\u003d1\\\\u0026shpt\\\\u003dgirls cardigan sweater | lands\\\\u0027 end\\\\u0026ga_client_id\\\\u003d1245476243.1575937452\",\"ip\":\"68.12.228.152\"}";
+        let json = COMPLEX_JSON;
         let (object, remainder) = get_object(json).unwrap();
         println!("{}", remainder);
         println!("{:?}", object);
+
+        let value = Value::Object(object);
+        assert_eq!(value.to_string(), json);
     }
 }
@@ -0,0 +1,100 @@
+use rmpv::Value as MsgValue;
+
+use crate::owned::{escape, unescape};
+use crate::{Error, OwnedEntry, OwnedValue};
+
+impl OwnedValue {
+    /// Encodes the value as MessagePack.
+    ///
+    /// Numbers become integers when they fit in an `i64` or `u64` and floats
+    /// otherwise. String escapes are decoded so the MessagePack strings hold
+    /// the actual text.
+    pub fn to_messagepack(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        rmpv::encode::write_value(&mut bytes, &to_msg_value(self)).expect("writing to a Vec can't fail");
+        return bytes;
+    }
+}
+
+/// Decodes a MessagePack document. Maps must have string keys, and binary or
+/// extension values are rejected since JSON has nothing to hold them.
+pub fn from_messagepack(bytes: &[u8]) -> Result<OwnedValue, Error> {
+    let mut rd = bytes;
+    let value = rmpv::decode::read_value(&mut rd).map_err(|e| Error::Decode(e.to_string()))?;
+    return from_msg_value(value);
+}
+
+fn to_msg_value(value: &OwnedValue) -> MsgValue {
+    return match value {
+        OwnedValue::Null => MsgValue::Nil,
+        OwnedValue::Boolean(b) => MsgValue::Boolean(b == "true"),
+        OwnedValue::String(s) => MsgValue::from(unescape(s)),
+        OwnedValue::Number(n) => {
+            if let Ok(i) = n.parse::<i64>() {
+                MsgValue::from(i)
+            } else if let Ok(u) = n.parse::<u64>() {
+                MsgValue::from(u)
+            } else {
+                MsgValue::from(n.parse::<f64>().unwrap_or(f64::NAN))
+            }
+        }
+        OwnedValue::Object(o) => MsgValue::Map(
+            o.iter()
+                .map(|e| (MsgValue::from(unescape(&e.key)), to_msg_value(&e.value)))
+                .collect(),
+        ),
+        OwnedValue::Array(a) => MsgValue::Array(a.iter().map(to_msg_value).collect()),
+    };
+}
+
+fn from_msg_value(value: MsgValue) -> Result<OwnedValue, Error> {
+    return match value {
+        MsgValue::Nil => Ok(OwnedValue::Null),
+        MsgValue::Boolean(b) => Ok(OwnedValue::Boolean(b.to_string())),
+        MsgValue::Integer(i) => Ok(OwnedValue::Number(i.to_string())),
+        MsgValue::F32(f) => Ok(OwnedValue::Number(f.to_string())),
+        MsgValue::F64(f) => Ok(OwnedValue::Number(f.to_string())),
+        MsgValue::String(s) => match s.into_str() {
+            Some(s) => Ok(OwnedValue::String(escape(&s))),
+            None => Err(Error::Decode("string is not valid UTF-8".to_string())),
+        },
+        MsgValue::Array(a) => Ok(OwnedValue::Array(
+            a.into_iter().map(from_msg_value).collect::<Result<_, _>>()?,
+        )),
+        MsgValue::Map(m) => {
+            let mut entries = Vec::with_capacity(m.len());
+            for (key, value) in m {
+                let key = match key {
+                    MsgValue::String(s) => s.into_str(),
+                    _ => None,
+                };
+                entries.push(OwnedEntry {
+                    key: escape(&key.ok_or_else(|| Error::Decode("map keys must be strings".to_string()))?),
+                    value: from_msg_value(value)?,
+                });
+            }
+            Ok(OwnedValue::Object(entries.into_boxed_slice()))
+        }
+        other => Err(Error::Decode(format!("{} has no JSON equivalent", other))),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_messagepack, parse_value, Error};
+
+    #[test]
+    fn messagepack_round_trip_test() {
+        let s = "{\"name\":\"a \\\"b\\\"\",\"id\":12,\"big\":18446744073709551615,\"score\":-1.5,\"ok\":true,\"none\":null,\"tags\":[\"x\",{\"y\":[]}]}";
+        let value = parse_value(s).unwrap().to_owned();
+
+        let bytes = value.to_messagepack();
+        assert_eq!(from_messagepack(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn from_messagepack_error_test() {
+        assert!(matches!(from_messagepack(&[0xc4, 0x01, 0x00]), Err(Error::Decode(_))));
+        assert!(matches!(from_messagepack(&[0x92, 0x01]), Err(Error::Decode(_))));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::Value;
+
+impl<'a> Value<'a> {
+    /// Counts the values in a newline delimited JSON document without parsing
+    /// them.
+    ///
+    /// Only brackets and quotes are tracked, so this is much cheaper than
+    /// parsing every line, but it doesn't check that the values are valid.
+    /// Blank lines are ignored.
+    pub fn json_lines_count(s: &str) -> usize {
+        let mut count = 0;
+        let mut depth: usize = 0;
+        let mut in_str = false;
+        let mut esc = false;
+        let mut in_scalar = false;
+
+        for c in s.chars() {
+            if in_str {
+                if esc {
+                    esc = false;
+                } else if c == '\\' {
+                    esc = true;
+                } else if c == '"' {
+                    in_str = false;
+                }
+                continue;
+            }
+
+            match c {
+                ' ' | '\t' | '\r' | '\n' => in_scalar = false,
+                '}' | ']' => depth = depth.saturating_sub(1),
+                '"' | '{' | '[' => {
+                    if depth == 0 && !in_scalar {
+                        count += 1;
+                    }
+                    if c == '"' {
+                        in_str = true;
+                    } else {
+                        depth += 1;
+                    }
+                }
+                _ => {
+                    if depth == 0 && !in_scalar {
+                        count += 1;
+                        in_scalar = true;
+                    }
+                }
+            }
+        }
+        return count;
+    }
+
+    /// Writes the value as JSON split over several lines, for output to
+    /// systems that work a line at a time. Objects and arrays open and close
+    /// on lines of their own, with one line per member in between. A member
+    /// that is itself an object or array opens on its key's line.
+    ///
+    /// There is no indentation and commas stay on the member before them, so
+    /// joining the lines with nothing in between gives compact JSON.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        write_lines(self, String::new(), "", &mut lines);
+        return lines;
+    }
+}
+
+/// Adds the lines for `value` to `lines`. Its first line starts with
+/// `prefix` and its last ends with `suffix`.
+fn write_lines(value: &Value, prefix: String, suffix: &str, lines: &mut Vec<String>) {
+    let (open, close, len) = match value {
+        Value::Object(o) if !o.is_empty() => ('{', '}', o.len()),
+        Value::Array(a) if !a.is_empty() => ('[', ']', a.len()),
+        _ => {
+            lines.push(format!("{}{}{}", prefix, value, suffix));
+            return;
+        }
+    };
+
+    lines.push(format!("{}{}", prefix, open));
+    for i in 0..len {
+        let comma = if i + 1 < len { "," } else { "" };
+        match value {
+            Value::Object(o) => write_lines(&o[i].value, format!("\"{}\":", o[i].key), comma, lines),
+            Value::Array(a) => write_lines(&a[i], String::new(), comma, lines),
+            _ => {}
+        }
+    }
+    lines.push(format!("{}{}", close, suffix));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Value};
+
+    #[test]
+    fn json_lines_count_test() {
+        let s = "{\"a\":1,\"b\":[1,2,{\"c\":\"}]\\\"\"}]}\n[1,[2]]\n\n\"str {\"\n42\r\n-1.5e3\ntrue\nnull\n{}\n";
+        let parsed = s.lines().filter(|l| parse_value(l).is_ok()).count();
+
+        assert_eq!(parsed, 8);
+        assert_eq!(Value::json_lines_count(s), parsed);
+        assert_eq!(Value::json_lines_count(""), 0);
+    }
+
+    #[test]
+    fn lines_test() {
+        let value = parse_value("{\"a\":1,\"b\":[true,{\"c\":\"x,y\"},[]],\"d\":{}}").unwrap();
+        let expected = vec!["{", "\"a\":1,", "\"b\":[", "true,", "{", "\"c\":\"x,y\"", "},", "[]", "],", "\"d\":{}", "}"];
+        assert_eq!(value.lines(), expected);
+
+        let joined = value.lines().concat();
+        assert_eq!(joined, value.to_string());
+        assert_eq!(parse_value(&joined).unwrap(), value);
+
+        assert_eq!(Value::Number("3").lines(), vec!["3"]);
+        assert_eq!(parse_value("[]").unwrap().lines(), vec!["[]"]);
+    }
+}
@@ -0,0 +1,796 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::slice::{Iter, Windows};
+
+use crate::{Entry, Error, OwnedArray, OwnedEntry, OwnedObject, OwnedValue, Value};
+
+/// Helpers for working with the entries of an `Object`.
+pub trait ObjectExt<'a> {
+    /// Selects the entries matching any of the `(key, value_pattern)` pairs.
+    ///
+    /// The key must match exactly. The pattern is matched against the compact
+    /// JSON of the value, so string values keep their quotes; `*` in the pattern
+    /// matches any run of characters.
+    fn select_values(&self, pred: &[(&str, &str)]) -> Vec<&Entry<'a>>;
+
+    /// Lists the keys found in both objects whose values differ, along with the
+    /// value from each side. Keys found in only one of the objects are skipped.
+    fn diff_values<'s>(&'s self, other: &'s [Entry<'a>]) -> Vec<(&'a str, &'s Value<'a>, &'s Value<'a>)>;
+
+    /// Follows `keys` down through nested objects and returns the value found
+    /// there. An empty path gives `None` since an object isn't a `Value`.
+    fn get_path(&self, keys: &[&str]) -> Option<&Value<'a>>;
+
+    /// Finds the value at `path`, as `get_path` does, and converts it to `T`.
+    /// Fails with `Error::NotFound` if there's nothing there, and otherwise
+    /// with whatever the conversion gives, `Error::WrongType` for the
+    /// conversions in this crate.
+    fn get_path_typed<'s, T: TryFrom<&'s Value<'a>, Error = Error>>(&'s self, path: &[&str]) -> Result<T, Error>
+    where
+        'a: 's;
+
+    /// Folds the entries, in order, into a single value.
+    fn fold<Acc, F: Fn(Acc, &Entry<'a>) -> Acc>(&self, init: Acc, f: F) -> Acc;
+
+    /// Splits the entries into groups named by `f`, keeping their order within
+    /// each group.
+    fn group_by<F: Fn(&Entry<'a>) -> String>(&self, f: F) -> HashMap<String, OwnedObject>;
+
+    /// Iterates over every run of `size` consecutive entries, as
+    /// `slice::windows` does. Panics if `size` is 0.
+    fn window(&self, size: usize) -> Windows<'_, Entry<'a>>;
+
+    /// Copies the object out with the first entry for `key` split off, giving
+    /// that entry's value (if there was one) and the remaining entries.
+    fn take(&self, key: &str) -> (Option<OwnedValue>, OwnedObject);
+
+    /// Maps every entry, in order, into a new owned object.
+    fn map<F: Fn(&Entry<'a>) -> OwnedEntry>(&self, f: F) -> OwnedObject;
+
+    /// Maps every entry, in order, into an element of a new owned array.
+    fn map_to_array<F: Fn(&Entry<'a>) -> OwnedValue>(&self, f: F) -> OwnedArray;
+
+    /// Copies the object with its entries rotated `n` places to the left, so
+    /// the first `n` come last. `n` may be larger than the object.
+    fn rotate_left(&self, n: usize) -> OwnedObject;
+
+    /// Copies the object with its entries rotated `n` places to the right, so
+    /// the last `n` come first. `n` may be larger than the object.
+    fn rotate_right(&self, n: usize) -> OwnedObject;
+
+    /// Splits the entries for `fields` into records, in order, skipping all
+    /// other entries. A record ends once it has every field or when a field
+    /// it already has comes up again, so an object logging
+    /// `{"level":"info","msg":"a","level":"warn","msg":"b"}` gives two records
+    /// for `["level", "msg"]`. A final record may be missing fields.
+    fn windows_by_key<'s, 'f>(&'s self, fields: &'f [&'f str]) -> KeyWindows<'s, 'a, 'f>;
+
+    /// Counts how many entries have each value. Strings are counted by their
+    /// contents, escapes included, and everything else by its compact JSON,
+    /// so `{"a":"x","b":"y","c":"x"}` gives `x` twice and `y` once. This means
+    /// the string `"1"` and the number `1` are counted together.
+    fn frequencies(&self) -> HashMap<String, usize>;
+
+    /// Copies the `n` entries with the largest numbers, keeping them in
+    /// document order. Entries that aren't numbers are skipped, and when
+    /// several entries tie for the last place the earliest ones are kept.
+    fn top_n_by_value(&self, n: usize) -> OwnedObject;
+
+    /// Copies the object with the entries for `keys` first, in that order,
+    /// and the others after them in document order. Keys that aren't in the
+    /// object are skipped, and every entry for a repeated key moves together.
+    fn reorder(&self, keys: &[&str]) -> OwnedObject;
+
+    /// Writes the object as compact JSON with the keys of it and every object
+    /// inside it sorted, so objects with the same entries give the same text
+    /// whatever order they were written in. Keys are compared as written,
+    /// escapes included, and repeated keys keep their order.
+    fn to_sorted_json(&self) -> String;
+}
+
+/// Iterator over the records found by `ObjectExt::windows_by_key`.
+pub struct KeyWindows<'s, 'a, 'f> {
+    entries: Iter<'s, Entry<'a>>,
+    fields: &'f [&'f str],
+}
+
+impl<'s, 'a, 'f> Iterator for KeyWindows<'s, 'a, 'f> {
+    type Item = HashMap<&'a str, &'s Value<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = HashMap::new();
+        while !self.fields.is_empty() && record.len() < self.fields.len() {
+            let rest = self.entries.as_slice();
+            let entry = match self.entries.next() {
+                Some(entry) => entry,
+                None => break,
+            };
+            if !self.fields.contains(&entry.key) {
+                continue;
+            }
+            if record.contains_key(entry.key) {
+                self.entries = rest.iter();
+                break;
+            }
+            record.insert(entry.key, &entry.value);
+        }
+        if record.is_empty() {
+            return None;
+        }
+        return Some(record);
+    }
+}
+
+impl<'a> ObjectExt<'a> for [Entry<'a>] {
+    fn select_values(&self, pred: &[(&str, &str)]) -> Vec<&Entry<'a>> {
+        return self
+            .iter()
+            .filter(|entry| {
+                let mut json = None;
+                pred.iter().any(|(key, pattern)| {
+                    *key == entry.key && glob(pattern, json.get_or_insert_with(|| entry.value.to_string()))
+                })
+            })
+            .collect();
+    }
+
+    fn diff_values<'s>(&'s self, other: &'s [Entry<'a>]) -> Vec<(&'a str, &'s Value<'a>, &'s Value<'a>)> {
+        return self
+            .iter()
+            .filter_map(|entry| {
+                let theirs = other.iter().find(|e| e.key == entry.key)?;
+                if theirs.value == entry.value {
+                    return None;
+                }
+                Some((entry.key, &entry.value, &theirs.value))
+            })
+            .collect();
+    }
+
+    fn get_path(&self, keys: &[&str]) -> Option<&Value<'a>> {
+        let (first, rest) = keys.split_first()?;
+        let mut cur = &self.iter().find(|e| e.key == *first)?.value;
+        for key in rest {
+            cur = cur.get(key)?;
+        }
+        return Some(cur);
+    }
+
+    fn get_path_typed<'s, T: TryFrom<&'s Value<'a>, Error = Error>>(&'s self, path: &[&str]) -> Result<T, Error>
+    where
+        'a: 's,
+    {
+        return T::try_from(self.get_path(path).ok_or(Error::NotFound)?);
+    }
+
+    fn fold<Acc, F: Fn(Acc, &Entry<'a>) -> Acc>(&self, init: Acc, f: F) -> Acc {
+        return self.iter().fold(init, f);
+    }
+
+    fn group_by<F: Fn(&Entry<'a>) -> String>(&self, f: F) -> HashMap<String, OwnedObject> {
+        let mut groups: HashMap<String, Vec<OwnedEntry>> = HashMap::new();
+        for entry in self.iter() {
+            groups.entry(f(entry)).or_default().push(entry.to_owned());
+        }
+        return groups.into_iter().map(|(k, v)| (k, v.into_boxed_slice())).collect();
+    }
+
+    fn window(&self, size: usize) -> Windows<'_, Entry<'a>> {
+        return self.windows(size);
+    }
+
+    fn take(&self, key: &str) -> (Option<OwnedValue>, OwnedObject) {
+        let index = self.iter().position(|e| e.key == key);
+        let rest = self
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != index)
+            .map(|(_, e)| e.to_owned())
+            .collect();
+        return (index.map(|i| self[i].value.to_owned()), rest);
+    }
+
+    fn map<F: Fn(&Entry<'a>) -> OwnedEntry>(&self, f: F) -> OwnedObject {
+        return self.iter().map(f).collect();
+    }
+
+    fn map_to_array<F: Fn(&Entry<'a>) -> OwnedValue>(&self, f: F) -> OwnedArray {
+        return self.iter().map(f).collect();
+    }
+
+    fn rotate_left(&self, n: usize) -> OwnedObject {
+        let mut rotated: Vec<OwnedEntry> = self.iter().map(Entry::to_owned).collect();
+        if !rotated.is_empty() {
+            let n = n % rotated.len();
+            rotated.rotate_left(n);
+        }
+        return rotated.into_boxed_slice();
+    }
+
+    fn rotate_right(&self, n: usize) -> OwnedObject {
+        let mut rotated: Vec<OwnedEntry> = self.iter().map(Entry::to_owned).collect();
+        if !rotated.is_empty() {
+            let n = n % rotated.len();
+            rotated.rotate_right(n);
+        }
+        return rotated.into_boxed_slice();
+    }
+
+    fn windows_by_key<'s, 'f>(&'s self, fields: &'f [&'f str]) -> KeyWindows<'s, 'a, 'f> {
+        return KeyWindows {
+            entries: self.iter(),
+            fields,
+        };
+    }
+
+    fn frequencies(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for entry in self.iter() {
+            let value = match &entry.value {
+                Value::String(s) => s.to_string(),
+                other => other.to_string(),
+            };
+            *counts.entry(value).or_insert(0) += 1;
+        }
+        return counts;
+    }
+
+    fn top_n_by_value(&self, n: usize) -> OwnedObject {
+        let mut numbers: Vec<(usize, f64)> = self
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| match e.value {
+                Value::Number(n) => n.parse::<f64>().ok().map(|n| (i, n)),
+                _ => None,
+            })
+            .collect();
+        numbers.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        numbers.truncate(n);
+
+        let mut kept: Vec<usize> = numbers.into_iter().map(|(i, _)| i).collect();
+        kept.sort_unstable();
+        return kept.into_iter().map(|i| self[i].to_owned()).collect();
+    }
+
+    fn reorder(&self, keys: &[&str]) -> OwnedObject {
+        let mut reordered: Vec<OwnedEntry> = Vec::with_capacity(self.len());
+        for (i, key) in keys.iter().enumerate() {
+            if !keys[..i].contains(key) {
+                reordered.extend(self.iter().filter(|e| e.key == *key).map(Entry::to_owned));
+            }
+        }
+        reordered.extend(self.iter().filter(|e| !keys.contains(&e.key)).map(Entry::to_owned));
+        return reordered.into_boxed_slice();
+    }
+
+    fn to_sorted_json(&self) -> String {
+        let mut out = String::new();
+        write_sorted_object(self, &mut out);
+        return out;
+    }
+}
+
+fn write_sorted_object(o: &[Entry], out: &mut String) {
+    let mut entries: Vec<&Entry> = o.iter().collect();
+    entries.sort_by_key(|e| e.key);
+    out.push('{');
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("\"{}\":", entry.key));
+        write_sorted(&entry.value, out);
+    }
+    out.push('}');
+}
+
+fn write_sorted(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(o) => write_sorted_object(o, out),
+        Value::Array(a) => {
+            out.push('[');
+            for (i, value) in a.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_sorted(value, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Copies the entries of this object whose keys also appear in `other`.
+    /// A value that isn't an object gives an empty object.
+    pub fn intersect_keys(&self, other: &[Entry]) -> OwnedObject {
+        let o = match self {
+            Value::Object(o) => o,
+            _ => return OwnedObject::default(),
+        };
+
+        return o
+            .iter()
+            .filter(|e| other.iter().any(|theirs| theirs.key == e.key))
+            .map(Entry::to_owned)
+            .collect();
+    }
+
+    /// Copies the entries of this object followed by the entries of `other`
+    /// whose keys it doesn't already have. A value that isn't an object is
+    /// treated as an empty one.
+    pub fn union_keys(&self, other: &[Entry]) -> OwnedObject {
+        let o: &[Entry] = match self {
+            Value::Object(o) => o,
+            _ => &[],
+        };
+
+        return o
+            .iter()
+            .chain(other.iter().filter(|theirs| !o.iter().any(|e| e.key == theirs.key)))
+            .map(Entry::to_owned)
+            .collect();
+    }
+
+    /// Copies the parts of this value that aren't in `other`.
+    ///
+    /// For two objects that's the entries whose keys `other` lacks or whose
+    /// values differ, and for two arrays the elements not found anywhere in
+    /// `other`. Otherwise the result is `null` if the values are equal and a
+    /// copy of this value if not.
+    pub fn difference(&self, other: &OwnedValue) -> OwnedValue {
+        return match (self, other) {
+            (Value::Object(o), OwnedValue::Object(theirs)) => OwnedValue::Object(
+                o.iter()
+                    .map(Entry::to_owned)
+                    .filter(|e| !theirs.iter().any(|t| t.key == e.key && t.value == e.value))
+                    .collect(),
+            ),
+            (Value::Array(a), OwnedValue::Array(theirs)) => {
+                OwnedValue::Array(a.iter().map(Value::to_owned).filter(|v| !theirs.contains(v)).collect())
+            }
+            _ => {
+                let value = self.to_owned();
+                if value == *other {
+                    OwnedValue::Null
+                } else {
+                    value
+                }
+            }
+        };
+    }
+
+    /// Copies `obj` without the entries whose values are `{}` or `[]`.
+    ///
+    /// Nested objects are compacted first, so an object left empty by the
+    /// removal of its own entries is removed as well. Arrays are copied as
+    /// they are.
+    pub fn compact_object(obj: &[Entry<'a>]) -> OwnedObject {
+        return obj
+            .iter()
+            .filter_map(|entry| {
+                let value = match &entry.value {
+                    Value::Object(o) => OwnedValue::Object(Value::compact_object(o)),
+                    other => other.to_owned(),
+                };
+                match &value {
+                    OwnedValue::Object(o) if o.is_empty() => None,
+                    OwnedValue::Array(a) if a.is_empty() => None,
+                    _ => Some(OwnedEntry {
+                        key: entry.key.to_string(),
+                        value,
+                    }),
+                }
+            })
+            .collect();
+    }
+}
+
+/// Helpers for working with the entries of an `OwnedObject`.
+pub trait OwnedObjectExt {
+    /// Merges `other` into a copy of `self`.
+    ///
+    /// Keys found in both objects get the value returned by
+    /// `strategy(key, self_value, other_value)`. Keys found only in `other` are
+    /// appended after the entries of `self`.
+    fn merge_strategy<F: Fn(&str, &OwnedValue, &OwnedValue) -> OwnedValue>(&self, other: &[OwnedEntry], strategy: F) -> OwnedObject;
+}
+
+impl OwnedObjectExt for [OwnedEntry] {
+    fn merge_strategy<F: Fn(&str, &OwnedValue, &OwnedValue) -> OwnedValue>(&self, other: &[OwnedEntry], strategy: F) -> OwnedObject {
+        let mut merged: Vec<OwnedEntry> = self
+            .iter()
+            .map(|entry| OwnedEntry {
+                key: entry.key.clone(),
+                value: match other.iter().find(|e| e.key == entry.key) {
+                    Some(theirs) => strategy(&entry.key, &entry.value, &theirs.value),
+                    None => entry.value.clone(),
+                },
+            })
+            .collect();
+
+        for entry in other.iter() {
+            if !self.iter().any(|e| e.key == entry.key) {
+                merged.push(entry.clone());
+            }
+        }
+        return merged.into_boxed_slice();
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    if !text.starts_with(first) {
+        return false;
+    }
+
+    let mut rest = &text[first.len()..];
+    let mut last = None;
+    for part in parts {
+        if let Some(prev) = last.replace(part) {
+            match rest.find(prev) {
+                Some(i) => rest = &rest[i + prev.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    return match last {
+        Some(last) => rest.ends_with(last),
+        None => rest.is_empty(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::glob;
+    use crate::{parse_value, Error, ObjectBuilder, ObjectExt, OwnedEntry, OwnedObject, OwnedObjectExt, OwnedValue, Value};
+
+    #[test]
+    fn glob_test() {
+        assert!(glob("abc", "abc"));
+        assert!(!glob("abc", "abcd"));
+        assert!(glob("a*", "abcd"));
+        assert!(glob("*d", "abcd"));
+        assert!(glob("a*c*", "abcd"));
+        assert!(!glob("a*e*", "abcd"));
+        assert!(glob("*", ""));
+    }
+
+    #[test]
+    fn select_values_test() {
+        let s = "[{\"type\":\"user\",\"name\":\"a\"},{\"type\":\"admin\",\"name\":\"b\"},{\"type\":\"user\",\"name\":\"c\"}]";
+        let users = match parse_value(s).unwrap() {
+            Value::Array(a) => a
+                .iter()
+                .filter(|v| match v {
+                    Value::Object(o) => !o.select_values(&[("type", "\"user\"")]).is_empty(),
+                    _ => false,
+                })
+                .count(),
+            _ => panic!("expected an array"),
+        };
+        assert_eq!(users, 2);
+
+        let v = parse_value("{\"type\":\"admin\",\"name\":\"ab\",\"id\":12}").unwrap();
+        let selected = v.as_object().unwrap().select_values(&[("type", "\"ad*\""), ("id", "1*")]);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].key, "type");
+        assert_eq!(selected[1].key, "id");
+    }
+
+    #[test]
+    fn diff_values_test() {
+        let a = parse_value("{\"a\":1,\"b\":\"x\",\"c\":[1,2],\"d\":true}").unwrap();
+        let b = parse_value("{\"a\":1,\"b\":\"y\",\"c\":[1,2],\"e\":true}").unwrap();
+        if let (Value::Object(a), Value::Object(b)) = (&a, &b) {
+            let diff = a.diff_values(b);
+            assert_eq!(diff, vec![("b", &Value::String("x"), &Value::String("y"))]);
+        } else {
+            panic!("expected objects");
+        }
+    }
+
+    #[test]
+    fn get_nested_string_test() {
+        let v = parse_value("{\"a\":{\"b\":{\"c\":\"found\",\"d\":1}}}").unwrap();
+        let o = v.as_object().unwrap();
+
+        assert_eq!(o.get_path(&["a", "b", "d"]), Some(&Value::Number("1")));
+        assert_eq!(Value::get_nested_string(o, &["a", "b", "c"]), Some("found"));
+        assert_eq!(Value::get_nested_string(o, &["a", "b", "d"]), None);
+        assert_eq!(Value::get_nested_string(o, &["a", "x", "c"]), None);
+        assert_eq!(Value::get_nested_string(o, &[]), None);
+    }
+
+    #[test]
+    fn get_nested_i64_test() {
+        let v = parse_value("{\"a\":{\"b\":42,\"c\":1.5,\"d\":\"42\"}}").unwrap();
+        let o = v.as_object().unwrap();
+
+        assert_eq!(Value::get_nested_i64(o, &["a", "b"]), Some(42));
+        assert_eq!(Value::get_nested_i64(o, &["a", "c"]), None);
+        assert_eq!(Value::get_nested_i64(o, &["a", "d"]), None);
+        assert_eq!(Value::get_nested_i64(o, &["b"]), None);
+    }
+
+    #[test]
+    fn get_path_typed_test() {
+        let v = parse_value("{\"a\":{\"b\":{\"id\":42,\"name\":\"widget\",\"ok\":true}}}").unwrap();
+        let o = v.as_object().unwrap();
+
+        assert_eq!(o.get_path_typed::<i64>(&["a", "b", "id"]).unwrap(), 42);
+        assert_eq!(o.get_path_typed::<&str>(&["a", "b", "name"]).unwrap(), "widget");
+        assert!(o.get_path_typed::<bool>(&["a", "b", "ok"]).unwrap());
+        assert!(matches!(o.get_path_typed::<i64>(&["a", "b", "name"]), Err(Error::WrongType("i64", "string"))));
+        assert!(matches!(o.get_path_typed::<&str>(&["a", "b"]), Err(Error::WrongType("string", "object"))));
+        assert!(matches!(o.get_path_typed::<i64>(&["a", "x", "id"]), Err(Error::NotFound)));
+        assert!(matches!(o.get_path_typed::<i64>(&[]), Err(Error::NotFound)));
+    }
+
+    #[test]
+    fn fold_test() {
+        let v = parse_value("{\"a\":1,\"b\":\"x\",\"c\":2.5,\"d\":\"y\"}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let sum = o.fold(0.0, |sum, e| match e.value {
+            Value::Number(n) => sum + n.parse::<f64>().unwrap(),
+            _ => sum,
+        });
+        assert_eq!(sum, 3.5);
+
+        let strings = o.fold(Vec::new(), |mut strings, e| {
+            if let Value::String(s) = e.value {
+                strings.push(s.to_string());
+            }
+            strings
+        });
+        assert_eq!(strings, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn intersect_keys_test() {
+        let a = parse_value("{\"a\":1,\"b\":2,\"c\":3}").unwrap();
+        let v = parse_value("{\"b\":4,\"c\":5,\"d\":6}").unwrap();
+        let b = v.as_object().unwrap();
+
+        assert_eq!(a.intersect_keys(b), owned_object("{\"b\":2,\"c\":3}"));
+        assert!(Value::Null.intersect_keys(b).is_empty());
+    }
+
+    #[test]
+    fn union_keys_test() {
+        let a = parse_value("{\"a\":1,\"b\":2,\"c\":3}").unwrap();
+        let v = parse_value("{\"b\":4,\"c\":5,\"d\":6}").unwrap();
+        let b = v.as_object().unwrap();
+
+        let union = a.union_keys(b);
+        assert_eq!(union.len(), 4);
+        assert_eq!(union, owned_object("{\"a\":1,\"b\":2,\"c\":3,\"d\":6}"));
+        assert_eq!(Value::Null.union_keys(b).len(), 3);
+    }
+
+    #[test]
+    fn difference_test() {
+        let a = parse_value("{\"a\":1,\"b\":[1,2],\"c\":\"x\",\"d\":null}").unwrap();
+        let b = parse_value("{\"a\":1,\"b\":[1],\"c\":\"y\",\"e\":true}").unwrap().to_owned();
+        assert_eq!(a.difference(&b), parse_value("{\"b\":[1,2],\"c\":\"x\",\"d\":null}").unwrap().to_owned());
+
+        let a = parse_value("[1,\"a\",{\"b\":2},3,1]").unwrap();
+        let b = parse_value("[3,{\"b\":2},4]").unwrap().to_owned();
+        assert_eq!(a.difference(&b), parse_value("[1,\"a\",1]").unwrap().to_owned());
+
+        let five = parse_value("5").unwrap();
+        assert_eq!(five.difference(&OwnedValue::from(5i64)), OwnedValue::Null);
+        assert_eq!(five.difference(&OwnedValue::from(6i64)), OwnedValue::from(5i64));
+        assert_eq!(a.difference(&OwnedValue::Null), a.to_owned());
+    }
+
+    #[test]
+    fn group_by_test() {
+        let v = parse_value("{\"a\":1,\"b\":\"x\",\"c\":2,\"d\":\"y\",\"e\":null}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let groups = o.group_by(|e| e.value.type_name().to_string());
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["number"], owned_object("{\"a\":1,\"c\":2}"));
+        assert_eq!(groups["string"], owned_object("{\"b\":\"x\",\"d\":\"y\"}"));
+        assert_eq!(groups["null"], owned_object("{\"e\":null}"));
+    }
+
+    #[test]
+    fn compact_object_test() {
+        let v = parse_value("{\"a\":{},\"b\":[],\"c\":1}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(Value::compact_object(o), owned_object("{\"c\":1}"));
+
+        let v = parse_value("{\"a\":{\"b\":{},\"c\":[],\"d\":{\"e\":[]},\"f\":[{}]}}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(Value::compact_object(o), owned_object("{\"a\":{\"f\":[{}]}}"));
+    }
+
+    #[test]
+    fn window_test() {
+        let v = parse_value("{\"a\":1,\"b\":2,\"c\":3,\"d\":4,\"e\":5}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let windows: Vec<Vec<&str>> = o.window(3).map(|w| w.iter().map(|e| e.key).collect()).collect();
+        assert_eq!(windows, vec![vec!["a", "b", "c"], vec!["b", "c", "d"], vec!["c", "d", "e"]]);
+        assert_eq!(o.window(6).count(), 0);
+    }
+
+    #[test]
+    fn take_test() {
+        let v = parse_value("{\"a\":1,\"b\":[2],\"c\":3}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let (taken, rest) = o.take("b");
+        assert_eq!(taken, Some(parse_value("[2]").unwrap().to_owned()));
+        assert_eq!(rest, owned_object("{\"a\":1,\"c\":3}"));
+
+        let (taken, rest) = o.take("x");
+        assert_eq!(taken, None);
+        assert_eq!(rest, owned_object("{\"a\":1,\"b\":[2],\"c\":3}"));
+
+        let v = parse_value("{\"a\":null}").unwrap();
+        let single = v.as_object().unwrap();
+        let (taken, rest) = single.take("a");
+        assert_eq!(taken, Some(OwnedValue::Null));
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn map_test() {
+        let v = parse_value("{\"a\":1,\"b\":\"x\",\"c\":[2],\"d\":2.5}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let mapped = o.map(|e| OwnedEntry {
+            key: e.key.to_string(),
+            value: match &e.value {
+                Value::Number(n) => OwnedValue::from(n.parse::<f64>().unwrap() * 2.0),
+                Value::String(s) => OwnedValue::String(s.to_uppercase()),
+                v => v.to_owned(),
+            },
+        });
+        assert_eq!(mapped, owned_object("{\"a\":2,\"b\":\"X\",\"c\":[2],\"d\":5}"));
+    }
+
+    #[test]
+    fn map_to_array_test() {
+        let v = parse_value("{\"a\":1,\"b\":2}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let pairs = o.map_to_array(|e| {
+            let pair = ObjectBuilder::new()
+                .insert("key", OwnedValue::String(e.key.to_string()))
+                .insert("value", e.value.to_owned())
+                .build();
+            OwnedValue::Object(pair)
+        });
+        let expected = parse_value("[{\"key\":\"a\",\"value\":1},{\"key\":\"b\",\"value\":2}]").unwrap().to_owned();
+        assert_eq!(OwnedValue::Array(pairs), expected);
+
+        let values = o.map_to_array(|e| e.value.to_owned());
+        assert_eq!(OwnedValue::Array(values), parse_value("[1,2]").unwrap().to_owned());
+    }
+
+    #[test]
+    fn rotate_test() {
+        let v = parse_value("{\"a\":1,\"b\":2,\"c\":3,\"d\":4,\"e\":5}").unwrap();
+        let o = v.as_object().unwrap();
+
+        let keys = |o: &OwnedObject| o.iter().map(|e| e.key.clone()).collect::<Vec<String>>().join("");
+        assert_eq!(keys(&o.rotate_left(2)), "cdeab");
+        assert_eq!(keys(&o.rotate_right(2)), "deabc");
+        assert_eq!(keys(&o.rotate_left(7)), "cdeab");
+        assert_eq!(o.rotate_left(5), owned_object("{\"a\":1,\"b\":2,\"c\":3,\"d\":4,\"e\":5}"));
+        assert!(o[..0].rotate_right(3).is_empty());
+    }
+
+    #[test]
+    fn windows_by_key_test() {
+        let log = "{\"timestamp\":1,\"level\":\"info\",\"host\":\"a\",\"msg\":\"start\",\"timestamp\":2,\"msg\":\"no level\",\"timestamp\":3,\"level\":\"warn\",\"msg\":\"slow\",\"timestamp\":4}";
+        let v = parse_value(log).unwrap();
+        let o = v.as_object().unwrap();
+
+        let records: Vec<_> = o.windows_by_key(&["timestamp", "level", "msg"]).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].len(), 3);
+        assert_eq!(records[0]["msg"], &Value::String("start"));
+        assert_eq!(records[1].get("level"), None);
+        assert_eq!(records[1]["timestamp"], &Value::Number("2"));
+        assert_eq!(records[2]["level"], &Value::String("warn"));
+        assert_eq!(records[3].len(), 1);
+
+        assert_eq!(o.windows_by_key(&[]).count(), 0);
+        assert_eq!(o.windows_by_key(&["missing"]).count(), 0);
+    }
+
+    #[test]
+    fn frequencies_test() {
+        let v = parse_value("{\"a\":\"x\",\"b\":\"y\",\"c\":\"x\",\"d\":\"z\"}").unwrap();
+        let o = v.as_object().unwrap();
+        let counts = o.frequencies();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["x"], 2);
+        assert_eq!(counts["y"], 1);
+        assert_eq!(counts["z"], 1);
+
+        let v = parse_value("{\"only\":null}").unwrap();
+        let o = v.as_object().unwrap();
+        let counts = o.frequencies();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["null"], 1);
+        assert!(o[..0].frequencies().is_empty());
+    }
+
+    #[test]
+    fn top_n_by_value_test() {
+        let v = parse_value("{\"a\":3,\"b\":1,\"c\":4,\"d\":2}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(o.top_n_by_value(2), owned_object("{\"a\":3,\"c\":4}"));
+        assert_eq!(o.top_n_by_value(10), owned_object("{\"a\":3,\"b\":1,\"c\":4,\"d\":2}"));
+        assert!(o.top_n_by_value(0).is_empty());
+
+        let v = parse_value("{\"a\":1,\"b\":\"9\",\"c\":2e0,\"d\":2,\"e\":-5}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(o.top_n_by_value(1), owned_object("{\"c\":2e0}"));
+        assert_eq!(o.top_n_by_value(3), owned_object("{\"a\":1,\"c\":2e0,\"d\":2}"));
+    }
+
+    #[test]
+    fn reorder_test() {
+        let v = parse_value("{\"c\":3,\"a\":1,\"b\":2}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(o.reorder(&["a", "b"]), owned_object("{\"a\":1,\"b\":2,\"c\":3}"));
+        assert_eq!(o.reorder(&["x", "b", "b"]), owned_object("{\"b\":2,\"c\":3,\"a\":1}"));
+        assert_eq!(o.reorder(&[]), owned_object("{\"c\":3,\"a\":1,\"b\":2}"));
+
+        let v = parse_value("{\"a\":1,\"b\":2,\"a\":3}").unwrap();
+        let o = v.as_object().unwrap();
+        assert_eq!(o.reorder(&["b"]), owned_object("{\"b\":2,\"a\":1,\"a\":3}"));
+    }
+
+    #[test]
+    fn to_sorted_json_test() {
+        let a_value = parse_value("{\"b\":1,\"a\":{\"y\":[{\"d\":1,\"c\":2}],\"x\":null},\"c\":\"s\"}").unwrap();
+        let a = a_value.as_object().unwrap();
+        let b_value = parse_value("{\"c\":\"s\",\"a\":{\"x\":null,\"y\":[{\"c\":2,\"d\":1}]},\"b\":1}").unwrap();
+        let b = b_value.as_object().unwrap();
+
+        let sorted = "{\"a\":{\"x\":null,\"y\":[{\"c\":2,\"d\":1}]},\"b\":1,\"c\":\"s\"}";
+        assert_eq!(a.to_sorted_json(), sorted);
+        assert_eq!(a.to_sorted_json(), b.to_sorted_json());
+        assert_eq!(a[..0].to_sorted_json(), "{}");
+        assert_ne!(a_value.to_string(), b_value.to_string());
+    }
+
+    fn owned_object(s: &str) -> OwnedObject {
+        match parse_value(s).unwrap().to_owned() {
+            OwnedValue::Object(o) => o,
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn merge_strategy_test() {
+        let a = owned_object("{\"a\":1,\"b\":[1],\"c\":\"x\"}");
+        let b = owned_object("{\"b\":[2],\"c\":\"y\",\"d\":true}");
+
+        let left = a.merge_strategy(&b, |_, l, _| l.clone());
+        assert_eq!(left, owned_object("{\"a\":1,\"b\":[1],\"c\":\"x\",\"d\":true}"));
+
+        let right = a.merge_strategy(&b, |_, _, r| r.clone());
+        assert_eq!(right, owned_object("{\"a\":1,\"b\":[2],\"c\":\"y\",\"d\":true}"));
+
+        let append = a.merge_strategy(&b, |_, l, r| match (l, r) {
+            (OwnedValue::Array(l), OwnedValue::Array(r)) => OwnedValue::Array(l.iter().chain(r.iter()).cloned().collect()),
+            _ => r.clone(),
+        });
+        assert_eq!(append, owned_object("{\"a\":1,\"b\":[1,2],\"c\":\"y\",\"d\":true}"));
+    }
+}
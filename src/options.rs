@@ -0,0 +1,634 @@
+use std::collections::HashSet;
+
+use crate::owned::{escape, unescape};
+use crate::{Error, OwnedEntry, OwnedValue};
+
+/// Controls what `parse_with_options` accepts.
+///
+/// Unlike `deserialize` and `parse_value`, which are forgiving about
+/// separators, this parser follows RFC 8259 exactly unless an option says
+/// otherwise. The default options accept standard JSON with no depth limit.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Accept a comma after the last member of an object or array.
+    pub allow_trailing_commas: bool,
+    /// Fail with `Error::DuplicateKey` when an object repeats a key. Keys are
+    /// compared after decoding escapes.
+    pub reject_duplicate_keys: bool,
+    /// Fail with `Error::TooDeep` when objects and arrays nest deeper than this.
+    pub max_depth: Option<usize>,
+    /// Treat `// line` and `/* block */` comments as whitespace.
+    pub allow_comments: bool,
+    /// Accept strings in single quotes, which may hold unescaped `"` and escaped
+    /// `\'`. They're stored double quote escaped like any other string.
+    pub allow_single_quoted_strings: bool,
+    /// Accept object keys written as bare identifiers, such as `{a: 1}`.
+    pub allow_unquoted_keys: bool,
+    /// Accept `NaN`, `Infinity` and `-Infinity`. JSON can't represent them, so
+    /// they're parsed as `null`.
+    pub allow_nan_and_infinity: bool,
+    /// Keep only the last value for a repeated key, at the position the key
+    /// first appeared. `reject_duplicate_keys` takes precedence.
+    pub dedup_keys: bool,
+    /// Accept the rest of what JSON5 allows: hexadecimal integers, a leading
+    /// `+`, a leading or trailing decimal point, the extra JavaScript string
+    /// escapes and line continuations, Unicode whitespace and, if unquoted
+    /// keys are allowed, Unicode letters in them. Numbers are stored in
+    /// standard form, so `0x1F` is `31` and `.5` is `0.5`.
+    pub allow_json5_extensions: bool,
+}
+
+impl ParseOptions {
+    /// Standard JSON only, no duplicate keys and at most 128 levels of nesting.
+    pub fn strict() -> ParseOptions {
+        return ParseOptions {
+            reject_duplicate_keys: true,
+            max_depth: Some(128),
+            ..ParseOptions::default()
+        };
+    }
+
+    /// Everything lenient turned on, for hand written files such as configs.
+    pub fn permissive() -> ParseOptions {
+        return ParseOptions {
+            allow_trailing_commas: true,
+            allow_comments: true,
+            allow_single_quoted_strings: true,
+            allow_unquoted_keys: true,
+            allow_nan_and_infinity: true,
+            dedup_keys: true,
+            allow_json5_extensions: true,
+            ..ParseOptions::default()
+        };
+    }
+}
+
+/// Parses a single JSON value as configured by `options`.
+pub fn parse_with_options(s: &str, options: &ParseOptions) -> Result<OwnedValue, Error> {
+    let mut parser = Parser {
+        s,
+        pos: 0,
+        depth: 0,
+        options,
+    };
+    parser.skip_whitespace()?;
+    let value = parser.value()?;
+    parser.skip_whitespace()?;
+    if parser.pos < s.len() {
+        return Err(parser.unexpected());
+    }
+    return Ok(value);
+}
+
+/// Parses standard RFC 8259 JSON, rejecting duplicate keys and nesting deeper
+/// than 128 levels. This is the one to use for untrusted input.
+pub fn parse_strict(s: &str) -> Result<OwnedValue, Error> {
+    return parse_with_options(s, &ParseOptions::strict());
+}
+
+/// Parses JSON with every lenient option turned on: comments, trailing commas,
+/// single quoted strings, unquoted keys, `NaN` and `Infinity`, the rest of
+/// JSON5, and repeated keys, of which the last wins.
+pub fn parse_permissive(s: &str) -> Result<OwnedValue, Error> {
+    return parse_with_options(s, &ParseOptions::permissive());
+}
+
+/// Parses the loosely written JSON found in hand edited files, such as
+/// `tsconfig.json`, VS Code settings or JSON5 configs. On top of RFC 8259 it
+/// accepts exactly:
+///
+/// - `// line` and `/* block */` comments,
+/// - a trailing comma after the last member of an object or array,
+/// - strings in single quotes, and the JavaScript escapes `\'`, `\xHH`,
+///   `\0` and `\v` along with line continuations,
+/// - object keys written as identifiers, such as `{name: 1}`,
+/// - `NaN`, `Infinity` and `-Infinity`, parsed as `null`,
+/// - hexadecimal integers, a leading `+` and a leading or trailing decimal
+///   point, all stored as standard JSON numbers,
+/// - Unicode whitespace, including a byte order mark,
+/// - repeated keys, of which the last value wins at the first key's place.
+///
+/// There's no nesting limit. Everything is converted to standard JSON as it's
+/// read, so the result writes out as strict JSON. Use `parse_strict` instead
+/// for input that should be standard JSON, such as anything from the
+/// network: it accepts none of the above, rejects repeated keys and stops at
+/// 128 levels of nesting. This accepts the same input as `parse_permissive`
+/// and `parse_json5`.
+pub fn parse_relaxed_json(s: &str) -> Result<OwnedValue, Error> {
+    return parse_permissive(s);
+}
+
+struct Parser<'a, 'o> {
+    s: &'a str,
+    pos: usize,
+    depth: usize,
+    options: &'o ParseOptions,
+}
+
+impl<'a, 'o> Parser<'a, 'o> {
+    fn rest(&self) -> &'a str {
+        return &self.s[self.pos..];
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self.rest().chars().next();
+    }
+
+    fn unexpected(&self) -> Error {
+        return match self.peek() {
+            Some(c) => Error::BadChar(c, self.pos),
+            None => Error::EarlyEnd,
+        };
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        return false;
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.eat(c) {
+            return Ok(());
+        }
+        return Err(self.unexpected());
+    }
+
+    /// Skips whitespace and, if allowed, comments. Fails with `Error::NoEnd`
+    /// for a block comment that's never closed.
+    fn skip_whitespace(&mut self) -> Result<(), Error> {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | '\r' => self.pos += 1,
+                '/' if self.options.allow_comments => {
+                    if !self.skip_comment()? {
+                        return Ok(());
+                    }
+                }
+                c if self.options.allow_json5_extensions && (c.is_whitespace() || c == '\u{feff}') => self.pos += c.len_utf8(),
+                _ => return Ok(()),
+            }
+        }
+        return Ok(());
+    }
+
+    /// Skips a comment. Returns false, having skipped nothing, if there's no
+    /// comment here, and fails with `Error::NoEnd` if a block comment is
+    /// never closed.
+    fn skip_comment(&mut self) -> Result<bool, Error> {
+        let rest = self.rest();
+        self.pos += if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            block.find("*/").ok_or(Error::NoEnd)? + 4
+        } else {
+            return Ok(false);
+        };
+        return Ok(true);
+    }
+
+    fn value(&mut self) -> Result<OwnedValue, Error> {
+        return match self.peek() {
+            Some('{') => self.nested(Parser::object),
+            Some('[') => self.nested(Parser::array),
+            Some('"') | Some('\'') => Ok(OwnedValue::String(self.string()?)),
+            Some('+') if !self.options.allow_json5_extensions => Err(self.unexpected()),
+            Some('-') | Some('+') if self.options.allow_nan_and_infinity && self.rest()[1..].starts_with(&['I', 'N'][..]) => {
+                self.pos += 1;
+                self.non_finite()
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => Ok(OwnedValue::Number(self.number()?)),
+            Some('+') | Some('.') => Ok(OwnedValue::Number(self.number()?)),
+            Some('t') => self.literal("true", OwnedValue::Boolean("true".to_string())),
+            Some('f') => self.literal("false", OwnedValue::Boolean("false".to_string())),
+            Some('n') => self.literal("null", OwnedValue::Null),
+            Some('N') | Some('I') if self.options.allow_nan_and_infinity => self.non_finite(),
+            _ => Err(self.unexpected()),
+        };
+    }
+
+    /// Parses `NaN` or `Infinity`, which JSON can't represent, as `null`.
+    fn non_finite(&mut self) -> Result<OwnedValue, Error> {
+        if self.rest().starts_with('N') {
+            return self.literal("NaN", OwnedValue::Null);
+        }
+        return self.literal("Infinity", OwnedValue::Null);
+    }
+
+    fn nested(&mut self, f: fn(&mut Self) -> Result<OwnedValue, Error>) -> Result<OwnedValue, Error> {
+        self.depth += 1;
+        if let Some(max) = self.options.max_depth {
+            if self.depth > max {
+                return Err(Error::TooDeep(self.pos));
+            }
+        }
+        let value = f(self)?;
+        self.depth -= 1;
+        return Ok(value);
+    }
+
+    fn literal(&mut self, word: &str, value: OwnedValue) -> Result<OwnedValue, Error> {
+        if !self.rest().starts_with(word) {
+            return Err(self.unexpected());
+        }
+        self.pos += word.len();
+        return Ok(value);
+    }
+
+    /// Parses the members of an object or array, leaving the position after the
+    /// closing bracket.
+    fn members<F: FnMut(&mut Self) -> Result<(), Error>>(&mut self, close: char, mut member: F) -> Result<(), Error> {
+        self.pos += 1;
+        self.skip_whitespace()?;
+        if self.eat(close) {
+            return Ok(());
+        }
+
+        loop {
+            member(self)?;
+            self.skip_whitespace()?;
+            if self.eat(close) {
+                return Ok(());
+            }
+            self.expect(',')?;
+            self.skip_whitespace()?;
+            if self.options.allow_trailing_commas && self.eat(close) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<OwnedValue, Error> {
+        let mut entries: Vec<OwnedEntry> = vec![];
+        let mut seen = HashSet::new();
+        self.members('}', |p| {
+            let start = p.pos;
+            let key = p.key()?;
+            let repeated = (p.options.reject_duplicate_keys || p.options.dedup_keys) && !seen.insert(unescape(&key));
+            if repeated && p.options.reject_duplicate_keys {
+                return Err(Error::DuplicateKey(key, start));
+            }
+            p.skip_whitespace()?;
+            p.expect(':')?;
+            p.skip_whitespace()?;
+            let value = p.value()?;
+            if repeated {
+                let decoded = unescape(&key);
+                if let Some(entry) = entries.iter_mut().find(|e| unescape(&e.key) == decoded) {
+                    entry.value = value;
+                }
+                return Ok(());
+            }
+            entries.push(OwnedEntry { key, value });
+            return Ok(());
+        })?;
+        return Ok(OwnedValue::Object(entries.into_boxed_slice()));
+    }
+
+    fn array(&mut self) -> Result<OwnedValue, Error> {
+        let mut values = vec![];
+        self.members(']', |p| {
+            values.push(p.value()?);
+            return Ok(());
+        })?;
+        return Ok(OwnedValue::Array(values.into_boxed_slice()));
+    }
+
+    /// Parses an object key, which may be a bare identifier if the options
+    /// allow it.
+    fn key(&mut self) -> Result<String, Error> {
+        if self.options.allow_unquoted_keys {
+            let unicode = self.options.allow_json5_extensions;
+            let rest = self.rest();
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$' || (unicode && c.is_alphanumeric())))
+                .unwrap_or(rest.len());
+            if len > 0 && !rest.starts_with(|c: char| c.is_numeric()) {
+                self.pos += len;
+                return Ok(rest[..len].to_string());
+            }
+        }
+        return self.string();
+    }
+
+    /// Parses a quoted string and returns its contents, escapes included.
+    /// Strings in the lenient forms the options allow are converted to
+    /// standard JSON escaping.
+    fn string(&mut self) -> Result<String, Error> {
+        let quote = match self.peek() {
+            Some('\'') if self.options.allow_single_quoted_strings => '\'',
+            _ => '"',
+        };
+        if quote != '"' || self.options.allow_json5_extensions {
+            return self.lenient_string(quote);
+        }
+
+        self.expect(quote)?;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                None => return Err(Error::NoEnd),
+                Some('"') => break,
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('"') | Some('\\') | Some('/') | Some('b') | Some('f') | Some('n') | Some('r') | Some('t') => self.pos += 1,
+                        Some('u') => self.hex_escape()?,
+                        _ => return Err(self.unexpected()),
+                    }
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.unexpected()),
+                Some(c) => self.pos += c.len_utf8(),
+            }
+        }
+        let contents = &self.s[start..self.pos];
+        self.pos += 1;
+        return Ok(contents.to_string());
+    }
+
+    /// Skips the `u` and four hex digits of a `\uXXXX` escape.
+    fn hex_escape(&mut self) -> Result<(), Error> {
+        self.pos += 1;
+        for _ in 0..4 {
+            match self.peek() {
+                Some(c) if c.is_ascii_hexdigit() => self.pos += 1,
+                _ => return Err(self.unexpected()),
+            }
+        }
+        return Ok(());
+    }
+
+    /// Parses a string quoted with `quote`, converting its contents to
+    /// standard JSON escaping as it goes.
+    fn lenient_string(&mut self, quote: char) -> Result<String, Error> {
+        let json5 = self.options.allow_json5_extensions;
+        self.expect(quote)?;
+        let mut converted = String::new();
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return Err(Error::NoEnd),
+            };
+            if c == quote {
+                self.pos += 1;
+                return Ok(converted);
+            }
+            match c {
+                '\\' => {
+                    self.pos += 1;
+                    self.lenient_escape(quote, &mut converted)?;
+                    continue;
+                }
+                '\n' | '\r' => return Err(self.unexpected()),
+                c if (c as u32) < 0x20 && !json5 => return Err(self.unexpected()),
+                '"' => converted.push_str("\\\""),
+                c if (c as u32) < 0x20 => converted.push_str(&escape(&c.to_string())),
+                c => converted.push(c),
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// Converts the escape after a backslash in a lenient string.
+    fn lenient_escape(&mut self, quote: char, converted: &mut String) -> Result<(), Error> {
+        let json5 = self.options.allow_json5_extensions;
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(Error::NoEnd),
+        };
+        match c {
+            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => {
+                converted.push('\\');
+                converted.push(c);
+            }
+            'u' => {
+                let start = self.pos;
+                self.hex_escape()?;
+                converted.push('\\');
+                converted.push_str(&self.s[start..self.pos]);
+                return Ok(());
+            }
+            '\'' if quote == '\'' || json5 => converted.push('\''),
+            _ if !json5 => return Err(self.unexpected()),
+            'x' => {
+                let hex = self.rest().get(1..3).filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()));
+                let code = match hex.and_then(|h| u32::from_str_radix(h, 16).ok()) {
+                    Some(code) => code,
+                    None => return Err(self.unexpected()),
+                };
+                converted.push_str(&escape(&std::char::from_u32(code).unwrap_or('\u{fffd}').to_string()));
+                self.pos += 3;
+                return Ok(());
+            }
+            '0' if !self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) => converted.push_str("\\u0000"),
+            '0'..='9' => return Err(self.unexpected()),
+            'v' => converted.push_str("\\u000b"),
+            '\r' => {
+                self.pos += 1;
+                self.eat('\n');
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            c => converted.push_str(&escape(&c.to_string())),
+        }
+        self.pos += c.len_utf8();
+        return Ok(());
+    }
+
+    fn digits(&mut self) -> Result<(), Error> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.unexpected());
+        }
+        return Ok(());
+    }
+
+    /// Parses a number following the RFC 8259 grammar, or the JSON5 one if the
+    /// options allow it, and returns its text.
+    fn number(&mut self) -> Result<String, Error> {
+        if self.options.allow_json5_extensions {
+            return self.json5_number();
+        }
+
+        let start = self.pos;
+        self.eat('-');
+        if !self.eat('0') {
+            self.digits()?;
+        }
+        if self.eat('.') {
+            self.digits()?;
+        }
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('+') {
+                self.eat('-');
+            }
+            self.digits()?;
+        }
+        return Ok(self.s[start..self.pos].to_string());
+    }
+
+    /// Parses a JSON5 number and rewrites it in standard JSON form.
+    fn json5_number(&mut self) -> Result<String, Error> {
+        let sign = if self.eat('-') { "-" } else { "" };
+        if sign.is_empty() {
+            self.eat('+');
+        }
+
+        let rest = self.rest();
+        if rest.starts_with("0x") || rest.starts_with("0X") {
+            self.pos += 2;
+            let start = self.pos;
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_hexdigit() {
+                    break;
+                }
+                self.pos += 1;
+            }
+            return match u128::from_str_radix(&self.s[start..self.pos], 16) {
+                Ok(n) => Ok(format!("{}{}", sign, n)),
+                Err(_) => Err(self.unexpected()),
+            };
+        }
+
+        let start = self.pos;
+        if !self.eat('0') && self.peek() != Some('.') {
+            self.digits()?;
+        }
+        let integer = &self.s[start..self.pos];
+        let mut fraction = "";
+        if self.eat('.') {
+            let start = self.pos;
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                self.pos += 1;
+            }
+            fraction = &self.s[start..self.pos];
+            if integer.is_empty() && fraction.is_empty() {
+                return Err(self.unexpected());
+            }
+        }
+        let start = self.pos;
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('+') {
+                self.eat('-');
+            }
+            self.digits()?;
+        }
+        let exponent = &self.s[start..self.pos];
+
+        let mut number = format!("{}{}", sign, if integer.is_empty() { "0" } else { integer });
+        if !fraction.is_empty() {
+            number.push('.');
+            number.push_str(fraction);
+        }
+        number.push_str(exponent);
+        return Ok(number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_permissive, parse_relaxed_json, parse_strict, parse_value, parse_with_options, Error, ParseOptions};
+
+    #[test]
+    fn parse_strict_accepts_standard_json_test() {
+        let s = "{\r\n\t\"a\": [1, -0.5, 2e10, 0, true, false, null],\n\t\"b\\u00e9\\n\": {\"c\": \"d\"}, \"e\": []\n}";
+        let value = parse_strict(s).unwrap();
+        assert_eq!(value.get("a"), Some(&parse_value("[1,-0.5,2e10,0,true,false,null]").unwrap().to_owned()));
+        assert!(value.get("b\\u00e9\\n").is_some());
+        assert_eq!(parse_strict(" 42 ").unwrap(), parse_value("42").unwrap().to_owned());
+    }
+
+    #[test]
+    fn parse_strict_rejects_test() {
+        let rejected = [
+            "{\"a\":1,}",
+            "[1,2,]",
+            "{'a':1}",
+            "{a:1}",
+            "[1 2]",
+            "{\"a\" 1}",
+            "[1,,2]",
+            "// comment\n[1]",
+            "[01]",
+            "[1.]",
+            "[.5]",
+            "[+1]",
+            "[NaN]",
+            "[\"a\tb\"]",
+            "[\"\\x\"]",
+            "[1] 2",
+            "[",
+        ];
+        for s in rejected.iter() {
+            assert!(parse_strict(s).is_err(), "accepted {:?}", s);
+        }
+
+        assert!(matches!(parse_strict("{\"a\":1,\"b\":2,\"a\":3}"), Err(Error::DuplicateKey(k, 13)) if k == "a"));
+        assert!(matches!(parse_strict("{\"a\":1,\"\\u0061\":3}"), Err(Error::DuplicateKey(_, _))));
+
+        let deep = format!("{}{}", "[".repeat(129), "]".repeat(129));
+        assert!(matches!(parse_strict(&deep), Err(Error::TooDeep(128))));
+        let ok = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(parse_strict(&ok).is_ok());
+    }
+
+    #[test]
+    fn parse_with_options_test() {
+        let options = ParseOptions {
+            allow_trailing_commas: true,
+            ..ParseOptions::default()
+        };
+        let value = parse_with_options("{\"a\":[1,2,],\"a\":3,}", &options).unwrap();
+        assert_eq!(value, parse_value("{\"a\":[1,2],\"a\":3}").unwrap().to_owned());
+    }
+
+    #[test]
+    fn parse_permissive_test() {
+        let config = "// settings\n{\n  name: 'my \"app\"',\n  version: 2, /* bumped */\n  tags: ['a', 'b\\'s',],\n  limit: Infinity,\n  ratio: -Infinity,\n  missing: NaN,\n}\n";
+        let expected = "{\"name\":\"my \\\"app\\\"\",\"version\":2,\"tags\":[\"a\",\"b's\"],\"limit\":null,\"ratio\":null,\"missing\":null}";
+        assert_eq!(parse_permissive(config).unwrap(), parse_value(expected).unwrap().to_owned());
+
+        let commented = "{\n  // the id\n  \"id\": 1, /* the\n  name */ \"name\": \"x\" // trailing\n}";
+        assert_eq!(parse_permissive(commented).unwrap(), parse_value("{\"id\":1,\"name\":\"x\"}").unwrap().to_owned());
+
+        let trailing = "{\"a\":[1,2,],\"b\":{\"c\":3,},}";
+        assert_eq!(parse_permissive(trailing).unwrap(), parse_value("{\"a\":[1,2],\"b\":{\"c\":3}}").unwrap().to_owned());
+
+        let repeated = parse_permissive("{\"a\":1,\"b\":2,a:3}").unwrap();
+        assert_eq!(repeated, parse_value("{\"a\":3,\"b\":2}").unwrap().to_owned());
+
+        assert!(parse_permissive("[1] / 2").is_err());
+        assert!(matches!(parse_permissive("[1 /* oops"), Err(Error::NoEnd)));
+        assert!(matches!(parse_permissive("[1] /* never closed"), Err(Error::NoEnd)));
+        assert!(matches!(parse_permissive("[1 /* fine */"), Err(Error::EarlyEnd)));
+        assert!(parse_permissive("{1a: 1}").is_err());
+        assert!(parse_with_options("{'a':1}", &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn parse_relaxed_json_test() {
+        // A tsconfig.json as generated by `tsc --init`, abridged, with a few
+        // more of the extensions added by hand.
+        let tsconfig = "\u{feff}{\n  /* Visit https://aka.ms/tsconfig to read more about this file */\n  \"compilerOptions\": {\n    \"target\": \"es2016\",                                  /* Set the JavaScript language version. */\n    \"module\": \"commonjs\",                                /* Specify what module code is generated. */\n    // \"rootDir\": \"./\",\n    'outDir': './dist',\n    strict: true,\n    \"skipLibCheck\": true,\n    maxNodeModuleJsDepth: 0x2,\n    \"strict\": false,\n  },\n  \"exclude\": [\"node_modules\", 'dist',],\n}\n";
+        let expected = "{\"compilerOptions\":{\"target\":\"es2016\",\"module\":\"commonjs\",\"outDir\":\"./dist\",\"strict\":false,\"skipLibCheck\":true,\"maxNodeModuleJsDepth\":2},\"exclude\":[\"node_modules\",\"dist\"]}";
+        let value = parse_relaxed_json(tsconfig).unwrap();
+        assert_eq!(value, parse_value(expected).unwrap().to_owned());
+        assert_eq!(parse_strict(&format!("{:?}", value)).unwrap(), value);
+
+        assert!(parse_strict(tsconfig).is_err());
+        assert!(parse_relaxed_json("{a: 1} // done\n").is_ok());
+        assert!(parse_relaxed_json("{a: 1,,}").is_err());
+        assert!(parse_relaxed_json("{a: undefined}").is_err());
+    }
+}
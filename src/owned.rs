@@ -0,0 +1,338 @@
+use std::fmt::{Debug, Formatter};
+
+use crate::{pointer, Entry, Value};
+
+pub type OwnedObject = Box<[OwnedEntry]>;
+pub type OwnedArray = Box<[OwnedValue]>;
+
+/// An owned copy of a `Value`.
+///
+/// Like `Value`, strings are kept exactly as they appeared between the quotes
+/// in the source document, escapes included.
+///
+/// `Debug` writes the value as JSON: compact with `{:?}` and indented by two
+/// spaces with `{:#?}`.
+#[derive(PartialEq, Clone)]
+pub enum OwnedValue {
+    Boolean(String),
+    String(String),
+    Number(String),
+    Null,
+    Object(OwnedObject),
+    Array(OwnedArray),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedEntry {
+    pub key: String,
+    pub value: OwnedValue,
+}
+
+/// Escapes `s` so it can be stored as the contents of an `OwnedValue::String`.
+pub(crate) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+/// Decodes the escapes in the contents of a JSON string. Invalid escapes are
+/// kept as they are.
+pub(crate) fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('b') => unescaped.push('\u{8}'),
+            Some('f') => unescaped.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let mut code = u32::from_str_radix(&hex, 16).ok();
+                if let Some(high @ 0xd800..=0xdbff) = code {
+                    let rest = chars.as_str();
+                    let low = rest.strip_prefix("\\u").and_then(|r| u32::from_str_radix(r.get(..4)?, 16).ok());
+                    code = match low {
+                        Some(low @ 0xdc00..=0xdfff) => {
+                            chars = rest[6..].chars();
+                            Some(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+                        }
+                        _ => None,
+                    };
+                }
+                match code.and_then(std::char::from_u32) {
+                    Some(c) => unescaped.push(c),
+                    None => {
+                        unescaped.push_str("\\u");
+                        unescaped.push_str(&hex);
+                    }
+                }
+            }
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    return unescaped;
+}
+
+impl<'a> Value<'a> {
+    /// Copies the value and everything under it out of the source document.
+    pub fn to_owned(&self) -> OwnedValue {
+        return match self {
+            Value::Boolean(b) => OwnedValue::Boolean(b.to_string()),
+            Value::String(s) => OwnedValue::String(s.to_string()),
+            Value::Number(n) => OwnedValue::Number(n.to_string()),
+            Value::Null => OwnedValue::Null,
+            Value::Object(o) => OwnedValue::Object(o.iter().map(Entry::to_owned).collect()),
+            Value::Array(a) => OwnedValue::Array(a.iter().map(Value::to_owned).collect()),
+        };
+    }
+}
+
+impl<'a> Entry<'a> {
+    pub fn to_owned(&self) -> OwnedEntry {
+        return OwnedEntry {
+            key: self.key.to_string(),
+            value: self.value.to_owned(),
+        };
+    }
+}
+
+impl OwnedValue {
+    /// The JSON name of the value's type, e.g. `"object"` or `"number"`.
+    pub fn type_name(&self) -> &'static str {
+        return match self {
+            OwnedValue::Boolean(_) => "boolean",
+            OwnedValue::String(_) => "string",
+            OwnedValue::Number(_) => "number",
+            OwnedValue::Null => "null",
+            OwnedValue::Object(_) => "object",
+            OwnedValue::Array(_) => "array",
+        };
+    }
+
+    /// Looks up `key` if the value is an object. The first matching entry wins.
+    pub fn get(&self, key: &str) -> Option<&OwnedValue> {
+        if let OwnedValue::Object(o) = self {
+            return o.iter().find(|e| e.key == key).map(|e| &e.value);
+        }
+        return None;
+    }
+
+    /// Always false: an `OwnedValue` owns its children outright, so the type
+    /// system rules out a value containing itself. This is here for callers
+    /// that check anyway, and so a shared, reference counted value type could
+    /// add a real check alongside it.
+    pub fn has_circular_reference(&self) -> bool {
+        return false;
+    }
+
+    /// Walks the tree depth first, calling `f` with the RFC 6901 pointer of each
+    /// node before descending into its children. `path` is the pointer of `self`,
+    /// usually `""` for the document root.
+    pub fn walk_mut<F: FnMut(&str, &mut OwnedValue)>(&mut self, path: &str, f: &mut F) {
+        f(path, self);
+
+        match self {
+            OwnedValue::Object(o) => {
+                for entry in o.iter_mut() {
+                    let path = pointer::push(path, &entry.key);
+                    entry.value.walk_mut(&path, f);
+                }
+            }
+            OwnedValue::Array(a) => {
+                for (i, value) in a.iter_mut().enumerate() {
+                    let path = pointer::push(path, &i.to_string());
+                    value.walk_mut(&path, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Debug for OwnedValue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let indent = if f.alternate() { Some(0) } else { None };
+        return write_json(self, indent, f);
+    }
+}
+
+/// Writes `value` as JSON, compact if `indent` is `None` and otherwise
+/// indented by two spaces per level, starting from `indent` levels in.
+fn write_json(value: &OwnedValue, indent: Option<usize>, f: &mut Formatter) -> std::fmt::Result {
+    let (open, close, len) = match value {
+        OwnedValue::Boolean(b) => return write!(f, "{}", b),
+        OwnedValue::String(s) => return write!(f, "\"{}\"", s),
+        OwnedValue::Number(n) => return write!(f, "{}", n),
+        OwnedValue::Null => return write!(f, "null"),
+        OwnedValue::Object(o) => ('{', '}', o.len()),
+        OwnedValue::Array(a) => ('[', ']', a.len()),
+    };
+
+    write!(f, "{}", open)?;
+    let inner = indent.map(|depth| depth + 1);
+    for i in 0..len {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        if let Some(depth) = inner {
+            write!(f, "\n{:width$}", "", width = depth * 2)?;
+        }
+        match value {
+            OwnedValue::Object(o) => {
+                let separator = if indent.is_some() { ": " } else { ":" };
+                write!(f, "\"{}\"{}", o[i].key, separator)?;
+                write_json(&o[i].value, inner, f)?;
+            }
+            OwnedValue::Array(a) => write_json(&a[i], inner, f)?,
+            _ => {}
+        }
+    }
+    match indent {
+        Some(depth) if len > 0 => write!(f, "\n{:width$}", "", width = depth * 2)?,
+        _ => {}
+    }
+    return write!(f, "{}", close);
+}
+
+impl From<bool> for OwnedValue {
+    fn from(b: bool) -> Self {
+        return OwnedValue::Boolean(b.to_string());
+    }
+}
+
+impl From<i64> for OwnedValue {
+    fn from(n: i64) -> Self {
+        return OwnedValue::Number(n.to_string());
+    }
+}
+
+impl From<u64> for OwnedValue {
+    fn from(n: u64) -> Self {
+        return OwnedValue::Number(n.to_string());
+    }
+}
+
+/// NaN and the infinities have no JSON representation and become `Null`.
+impl From<f64> for OwnedValue {
+    fn from(n: f64) -> Self {
+        if !n.is_finite() {
+            return OwnedValue::Null;
+        }
+        return OwnedValue::Number(n.to_string());
+    }
+}
+
+/// The text is escaped as needed.
+impl From<&str> for OwnedValue {
+    fn from(s: &str) -> Self {
+        return OwnedValue::String(escape(s));
+    }
+}
+
+/// The text is escaped as needed.
+impl From<String> for OwnedValue {
+    fn from(s: String) -> Self {
+        return OwnedValue::from(s.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::owned::{escape, unescape};
+    use crate::{deserialize, parse_value, OwnedArray, OwnedValue, Value};
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(escape("a\"b\\c\nd\u{1}"), "a\\\"b\\\\c\\nd\\u0001");
+    }
+
+    #[test]
+    fn unescape_test() {
+        assert_eq!(unescape("a\\\"b\\\\c\\nd\\u0001"), "a\"b\\c\nd\u{1}");
+        assert_eq!(unescape("\\/\\u00e9\\ud83d\\ude00"), "/\u{e9}\u{1f600}");
+        assert_eq!(unescape("\\ud83d!\\uzz"), "\\ud83d!\\uzz");
+    }
+
+    #[test]
+    fn from_test() {
+        assert_eq!(OwnedValue::from(true), OwnedValue::Boolean("true".to_string()));
+        assert_eq!(OwnedValue::from(-3i64), OwnedValue::Number("-3".to_string()));
+        assert_eq!(OwnedValue::from(1.5), OwnedValue::Number("1.5".to_string()));
+        assert_eq!(OwnedValue::from(f64::INFINITY), OwnedValue::Null);
+        assert_eq!(OwnedValue::from("a\"b"), OwnedValue::String("a\\\"b".to_string()));
+    }
+
+    #[test]
+    fn collect_array_test() {
+        let a = (0..5).map(|i| OwnedValue::from(i as i64)).collect::<OwnedArray>();
+        assert_eq!(OwnedValue::Array(a), parse_value("[0,1,2,3,4]").unwrap().to_owned());
+
+        let parsed = parse_value("[1,\"a\",2,null]").unwrap();
+        let numbers: OwnedArray = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|v| v.type_name() == "number")
+            .map(Value::to_owned)
+            .collect();
+        assert_eq!(OwnedValue::Array(numbers), parse_value("[1,2]").unwrap().to_owned());
+    }
+
+    #[test]
+    fn debug_test() {
+        let s = "{\"a\":[1,{\"b\":null}],\"c\":\"x\\\"y\",\"d\":{},\"e\":[]}";
+        let value = parse_value(s).unwrap().to_owned();
+
+        assert_eq!(format!("{:?}", value), s);
+        assert_eq!(parse_value(&format!("{:?}", value)).unwrap().to_owned(), value);
+
+        let pretty = "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": \"x\\\"y\",\n  \"d\": {},\n  \"e\": []\n}";
+        assert_eq!(format!("{:#?}", value), pretty);
+        assert_eq!(parse_value(&format!("{:#?}", value)).unwrap().to_owned(), value);
+    }
+
+    #[test]
+    fn has_circular_reference_test() {
+        let value = parse_value("{\"a\":{\"b\":[{\"c\":null}]}}").unwrap().to_owned();
+        assert!(!value.has_circular_reference());
+        assert!(!OwnedValue::Null.has_circular_reference());
+    }
+
+    #[test]
+    fn walk_mut_test() {
+        let s = "{\"a\":{\"b\":1},\"c\":[2,3],\"d/e~\":4}";
+        let (object, _) = deserialize(s).unwrap();
+        let mut value = Value::Object(object).to_owned();
+
+        let mut paths = vec![];
+        value.walk_mut("", &mut |path, node| {
+            paths.push(path.to_string());
+            if let OwnedValue::Number(n) = node {
+                *n = (n.parse::<i64>().unwrap() * 2).to_string();
+            }
+        });
+
+        assert_eq!(paths, vec!["", "/a", "/a/b", "/c", "/c/0", "/c/1", "/d~1e~0"]);
+
+        let (expected, _) = deserialize("{\"a\":{\"b\":2},\"c\":[4,6],\"d/e~\":8}").unwrap();
+        assert_eq!(value, Value::Object(expected).to_owned());
+    }
+}
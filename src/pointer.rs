@@ -0,0 +1,313 @@
+use crate::{Entry, OwnedEntry, OwnedObject, OwnedValue, Value};
+
+/// Escapes a single reference token as described in RFC 6901.
+pub(crate) fn escape(token: &str) -> String {
+    return token.replace('~', "~0").replace('/', "~1");
+}
+
+/// Appends `token` to the pointer `path`.
+pub(crate) fn push(path: &str, token: &str) -> String {
+    return format!("{}/{}", path, escape(token));
+}
+
+/// Splits an RFC 6901 pointer into its unescaped reference tokens. Returns
+/// `None` if the pointer is neither empty nor starts with `/`.
+pub(crate) fn tokens(ptr: &str) -> Option<Vec<String>> {
+    if ptr.is_empty() {
+        return Some(vec![]);
+    }
+    if !ptr.starts_with('/') {
+        return None;
+    }
+    return Some(ptr[1..].split('/').map(|t| t.replace("~1", "/").replace("~0", "~")).collect());
+}
+
+impl<'a> Value<'a> {
+    /// Looks up a value by RFC 6901 JSON Pointer, e.g. `"/a/0/b"`. The empty
+    /// pointer refers to the value itself.
+    pub fn pointer(&self, ptr: &str) -> Option<&Value<'a>> {
+        let mut cur = self;
+        for token in tokens(ptr)? {
+            cur = cur.at(&token)?;
+        }
+        return Some(cur);
+    }
+
+    /// The `depth` of the value at the RFC 6901 pointer `ptr`, if there is one.
+    pub fn max_depth_at_path(&self, ptr: &str) -> Option<usize> {
+        return self.pointer(ptr).map(Value::depth);
+    }
+
+    /// Walks the tree depth first, calling `f` with the RFC 6901 pointer of each
+    /// node before descending into its children. `path` is the pointer of `self`,
+    /// usually `""` for the document root.
+    pub fn walk<F: FnMut(&str, &Value<'a>)>(&self, path: &str, f: &mut F) {
+        f(path, self);
+
+        match self {
+            Value::Object(o) => {
+                for entry in o.iter() {
+                    entry.value.walk(&push(path, entry.key), f);
+                }
+            }
+            Value::Array(a) => {
+                for (i, value) in a.iter().enumerate() {
+                    value.walk(&push(path, &i.to_string()), f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Searches depth first for `needle` and returns the RFC 6901 pointer of
+    /// the first match, which may be `""` for the value itself.
+    pub fn path_of(&self, needle: &Value) -> Option<String> {
+        return find(self, needle, String::new());
+    }
+
+    /// Formats an RFC 6901 pointer for people to read, so `"/a/b/0"` becomes
+    /// `"a → b → [0]"` and `""` becomes `"<root>"`. Tokens that are all digits
+    /// are shown as indexes. A malformed pointer is returned as it is.
+    pub fn pretty_path(ptr: &str) -> String {
+        let tokens = match tokens(ptr) {
+            Some(tokens) => tokens,
+            None => return ptr.to_string(),
+        };
+        if tokens.is_empty() {
+            return "<root>".to_string();
+        }
+
+        return tokens
+            .iter()
+            .map(|t| {
+                if !t.is_empty() && t.chars().all(|c| c.is_ascii_digit()) {
+                    return format!("[{}]", t);
+                }
+                t.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(" → ");
+    }
+
+    /// Flattens the value into an object mapping the RFC 6901 pointer of
+    /// every leaf to the leaf, so `{"a":{"b":[1,{}]}}` becomes
+    /// `{"/a/b/0":1,"/a/b/1":{}}`. Leaves are scalars and empty objects and
+    /// arrays, and a scalar at the root is keyed by `""`.
+    pub fn to_flat_json(&self) -> OwnedObject {
+        let mut flat = vec![];
+        self.walk("", &mut |path, value| {
+            let leaf = match value {
+                Value::Object(o) => o.is_empty(),
+                Value::Array(a) => a.is_empty(),
+                _ => true,
+            };
+            if leaf {
+                flat.push(OwnedEntry {
+                    key: path.to_string(),
+                    value: value.to_owned(),
+                });
+            }
+        });
+        return flat.into_boxed_slice();
+    }
+
+    /// Rebuilds a value from the output of `to_flat_json`.
+    ///
+    /// Containers whose tokens are exactly `0`, `1`, `2` and so on, in order,
+    /// become arrays, so an object with keys like that comes back as an
+    /// array. Keys that aren't pointers are skipped, as is a value for a
+    /// pointer that other pointers go through.
+    pub fn from_flat_json(flat: &[OwnedEntry]) -> OwnedValue {
+        let leaves: Vec<(Vec<String>, &OwnedValue)> = flat.iter().filter_map(|e| Some((tokens(&e.key)?, &e.value))).collect();
+        let leaves: Vec<Leaf> = leaves.iter().map(|(t, v)| (&t[..], *v)).collect();
+        return unflatten(&leaves);
+    }
+}
+
+/// The reference tokens left to follow to a leaf, and the leaf.
+type Leaf<'t> = (&'t [String], &'t OwnedValue);
+
+fn unflatten(leaves: &[Leaf]) -> OwnedValue {
+    let mut children: Vec<(&str, Vec<Leaf>)> = vec![];
+    for (tokens, value) in leaves.iter() {
+        let (first, rest) = match tokens.split_first() {
+            Some(split) => split,
+            None if leaves.len() == 1 => return (*value).clone(),
+            None => continue,
+        };
+        match children.iter_mut().find(|(token, _)| token == first) {
+            Some((_, grouped)) => grouped.push((rest, value)),
+            None => children.push((first, vec![(rest, value)])),
+        }
+    }
+
+    if !children.is_empty() && children.iter().enumerate().all(|(i, (token, _))| *token == i.to_string()) {
+        return OwnedValue::Array(children.iter().map(|(_, grouped)| unflatten(grouped)).collect());
+    }
+    return OwnedValue::Object(
+        children
+            .iter()
+            .map(|(token, grouped)| OwnedEntry {
+                key: token.to_string(),
+                value: unflatten(grouped),
+            })
+            .collect(),
+    );
+}
+
+impl<'a> Value<'a> {
+    /// Replaces every object of the form `{"$ref": "#/some/pointer", ...}` with
+    /// the value the pointer refers to, looked up with `defs` as the root. So
+    /// with `defs` of `{"defs": {"id": {"type": "integer"}}}`, a `$ref` of
+    /// `"#/defs/id"` is replaced by `{"type": "integer"}`.
+    ///
+    /// Definitions are resolved too. References that aren't local, can't be
+    /// found or refer back to themselves are left as they are.
+    pub fn resolve_references(&self, defs: &[Entry]) -> OwnedValue {
+        return resolve(self, defs, &mut vec![]);
+    }
+}
+
+fn lookup<'d, 'a>(defs: &'d [Entry<'a>], ptr: &str) -> Option<&'d Value<'a>> {
+    let tokens = tokens(ptr)?;
+    let (first, rest) = tokens.split_first()?;
+    let mut cur = &defs.iter().find(|e| e.key == first)?.value;
+    for token in rest {
+        cur = cur.at(token)?;
+    }
+    return Some(cur);
+}
+
+fn resolve(value: &Value, defs: &[Entry], stack: &mut Vec<String>) -> OwnedValue {
+    return match value {
+        Value::Object(o) => {
+            let reference = match value.get("$ref") {
+                Some(Value::String(r)) if r.starts_with('#') => Some(*r),
+                _ => None,
+            };
+            if let Some(r) = reference {
+                if !stack.iter().any(|s| s == r) {
+                    if let Some(target) = lookup(defs, &r[1..]) {
+                        stack.push(r.to_string());
+                        let resolved = resolve(target, defs, stack);
+                        stack.pop();
+                        return resolved;
+                    }
+                }
+            }
+            OwnedValue::Object(
+                o.iter()
+                    .map(|e| OwnedEntry {
+                        key: e.key.to_string(),
+                        value: resolve(&e.value, defs, stack),
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(a) => OwnedValue::Array(a.iter().map(|v| resolve(v, defs, stack)).collect()),
+        other => other.to_owned(),
+    };
+}
+
+fn find(value: &Value, needle: &Value, path: String) -> Option<String> {
+    if value == needle {
+        return Some(path);
+    }
+
+    return match value {
+        Value::Object(o) => o.iter().find_map(|e| find(&e.value, needle, push(&path, e.key))),
+        Value::Array(a) => a
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| find(v, needle, push(&path, &i.to_string()))),
+        _ => None,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_value;
+    use crate::pointer::tokens;
+    use crate::{OwnedValue, Value};
+
+    #[test]
+    fn tokens_test() {
+        assert_eq!(tokens(""), Some(vec![]));
+        assert_eq!(tokens("/a~1b/~01"), Some(vec!["a/b".to_string(), "~1".to_string()]));
+        assert_eq!(tokens("a"), None);
+    }
+
+    #[test]
+    fn pointer_test() {
+        let value = parse_value("{\"a\":[{\"b\":1},{\"c/d\":true}]}").unwrap();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/a/0/b"), Some(&Value::Number("1")));
+        assert_eq!(value.pointer("/a/1/c~1d"), Some(&Value::Boolean("true")));
+        assert_eq!(value.pointer("/a/2"), None);
+        assert_eq!(value.pointer("/b"), None);
+    }
+
+    #[test]
+    fn max_depth_at_path_test() {
+        let value = parse_value("{\"a\":{\"b\":{\"c\":1}},\"d\":[]}").unwrap();
+        assert_eq!(value.max_depth_at_path(""), Some(3));
+        assert_eq!(value.max_depth_at_path("/a"), Some(2));
+        assert_eq!(value.max_depth_at_path("/a/b/c"), Some(0));
+        assert_eq!(value.max_depth_at_path("/d"), Some(1));
+        assert_eq!(value.max_depth_at_path("/x"), None);
+    }
+
+    #[test]
+    fn path_of_test() {
+        let value = parse_value("{\"a\":[{\"b\":1},{\"c/d\":\"x\"}],\"e\":\"x\"}").unwrap();
+
+        let needle = Value::String("x");
+        let path = value.path_of(&needle).unwrap();
+        assert_eq!(path, "/a/1/c~1d");
+        assert_eq!(value.pointer(&path), Some(&needle));
+
+        assert_eq!(value.path_of(&value), Some(String::new()));
+        assert_eq!(value.path_of(&Value::Number("2")), None);
+    }
+
+    #[test]
+    fn pretty_path_test() {
+        assert_eq!(Value::pretty_path("/a/b/0"), "a → b → [0]");
+        assert_eq!(Value::pretty_path(""), "<root>");
+        assert_eq!(Value::pretty_path("/x"), "x");
+        assert_eq!(Value::pretty_path("/a~1b/+1/"), "a/b → +1 → ");
+        assert_eq!(Value::pretty_path("a/b"), "a/b");
+    }
+
+    #[test]
+    fn flat_json_test() {
+        let value = parse_value("{\"a\":{\"b\":[1,{\"c\":\"x\"}],\"d/e\":null},\"f\":[],\"g\":{},\"h\":[[true]]}").unwrap();
+        let flat = value.to_flat_json();
+        let expected = parse_value("{\"/a/b/0\":1,\"/a/b/1/c\":\"x\",\"/a/d~1e\":null,\"/f\":[],\"/g\":{},\"/h/0/0\":true}").unwrap().to_owned();
+        assert_eq!(OwnedValue::Object(flat.clone()), expected);
+        assert_eq!(Value::from_flat_json(&flat), value.to_owned());
+
+        let scalar = Value::Number("5").to_flat_json();
+        assert_eq!(scalar[0].key, "");
+        assert_eq!(Value::from_flat_json(&scalar), OwnedValue::from(5i64));
+
+        let keyed_by_index = parse_value("{\"0\":\"a\",\"1\":\"b\"}").unwrap();
+        assert_eq!(Value::from_flat_json(&keyed_by_index.to_flat_json()), parse_value("[\"a\",\"b\"]").unwrap().to_owned());
+        let out_of_order = parse_value("{\"1\":\"a\",\"0\":\"b\"}").unwrap();
+        assert_eq!(Value::from_flat_json(&out_of_order.to_flat_json()), out_of_order.to_owned());
+    }
+
+    #[test]
+    fn resolve_references_test() {
+        let doc = parse_value("{\"defs\":{\"id\":{\"type\":\"integer\"},\"user\":{\"type\":\"object\",\"properties\":{\"id\":{\"$ref\":\"#/defs/id\"}}},\"loop\":{\"$ref\":\"#/defs/loop\"}},\"properties\":{\"owner\":{\"$ref\":\"#/defs/user\"},\"members\":{\"type\":\"array\",\"items\":{\"$ref\":\"#/defs/user\"}},\"other\":{\"$ref\":\"#/defs/missing\"},\"self\":{\"$ref\":\"#/defs/loop\"}}}").unwrap();
+        let defs = match &doc {
+            Value::Object(o) => o,
+            _ => panic!("expected an object"),
+        };
+
+        let resolved = doc.get("properties").unwrap().resolve_references(defs);
+        let user = "{\"type\":\"object\",\"properties\":{\"id\":{\"type\":\"integer\"}}}";
+        let expected = format!("{{\"owner\":{},\"members\":{{\"type\":\"array\",\"items\":{}}},\"other\":{{\"$ref\":\"#/defs/missing\"}},\"self\":{{\"$ref\":\"#/defs/loop\"}}}}", user, user);
+        assert_eq!(resolved, parse_value(&expected).unwrap().to_owned());
+    }
+}
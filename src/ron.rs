@@ -0,0 +1,93 @@
+use ::ron::{Map, Number, Value as RonValue};
+
+use crate::owned::{escape, unescape};
+use crate::{Error, OwnedEntry, OwnedValue, Value};
+
+impl<'a> Value<'a> {
+    /// Writes the value as RON.
+    ///
+    /// Objects become maps with string keys, keeping their order, and `null`
+    /// becomes `None`. Numbers become integers when they fit in an `i64` and
+    /// floats otherwise. String escapes are decoded and re-escaped for RON.
+    pub fn to_ron_string(&self) -> Result<String, Error> {
+        return ::ron::to_string(&to_ron_value(self)).map_err(|e| Error::Decode(e.to_string()));
+    }
+}
+
+/// Reads a RON document. Maps must have string keys, `()` and `None` become
+/// `null`, `Some(x)` becomes `x` and chars become strings.
+pub fn from_ron(s: &str) -> Result<OwnedValue, Error> {
+    let value: RonValue = ::ron::from_str(s).map_err(|e| Error::Decode(e.to_string()))?;
+    return from_ron_value(value);
+}
+
+fn to_ron_value(value: &Value) -> RonValue {
+    return match value {
+        Value::Null => RonValue::Option(None),
+        Value::Boolean(b) => RonValue::Bool(*b == "true"),
+        Value::String(s) => RonValue::String(unescape(s)),
+        Value::Number(n) => match n.parse::<i64>() {
+            Ok(i) => RonValue::Number(Number::Integer(i)),
+            Err(_) => RonValue::Number(Number::from(n.parse::<f64>().unwrap_or(f64::NAN))),
+        },
+        Value::Object(o) => {
+            let mut map = Map::new();
+            for entry in o.iter() {
+                map.insert(RonValue::String(unescape(entry.key)), to_ron_value(&entry.value));
+            }
+            RonValue::Map(map)
+        }
+        Value::Array(a) => RonValue::Seq(a.iter().map(to_ron_value).collect()),
+    };
+}
+
+fn from_ron_value(value: RonValue) -> Result<OwnedValue, Error> {
+    return match value {
+        RonValue::Unit | RonValue::Option(None) => Ok(OwnedValue::Null),
+        RonValue::Option(Some(v)) => from_ron_value(*v),
+        RonValue::Bool(b) => Ok(OwnedValue::from(b)),
+        RonValue::Char(c) => Ok(OwnedValue::from(c.to_string())),
+        RonValue::String(s) => Ok(OwnedValue::from(s)),
+        RonValue::Number(Number::Integer(i)) => Ok(OwnedValue::from(i)),
+        RonValue::Number(Number::Float(f)) => Ok(OwnedValue::from(f.get())),
+        RonValue::Seq(s) => Ok(OwnedValue::Array(s.into_iter().map(from_ron_value).collect::<Result<_, _>>()?)),
+        RonValue::Map(m) => {
+            let mut entries = Vec::with_capacity(m.len());
+            for (key, value) in m {
+                let key = match key {
+                    RonValue::String(s) => escape(&s),
+                    _ => return Err(Error::Decode("map keys must be strings".to_string())),
+                };
+                entries.push(OwnedEntry {
+                    key,
+                    value: from_ron_value(value)?,
+                });
+            }
+            Ok(OwnedValue::Object(entries.into_boxed_slice()))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_ron, parse_value, Error};
+
+    #[test]
+    fn ron_round_trip_test() {
+        let s = "{\"name\":\"a \\\"b\\\"\\n\",\"id\":12,\"score\":-1.5,\"ok\":true,\"none\":null,\"tags\":[\"x\",{\"y\":[]}]}";
+        let value = parse_value(s).unwrap();
+
+        let ron = value.to_ron_string().unwrap();
+        assert!(ron.contains("\"none\":None"), "{}", ron);
+        assert_eq!(from_ron(&ron).unwrap(), value.to_owned());
+    }
+
+    #[test]
+    fn from_ron_test() {
+        let value = from_ron("{\"a\": Some('c'), \"b\": (), \"c\": [1, 2.5]}").unwrap();
+        assert_eq!(value, parse_value("{\"a\":\"c\",\"b\":null,\"c\":[1,2.5]}").unwrap().to_owned());
+
+        assert!(matches!(from_ron("{1: 2}"), Err(Error::Decode(_))));
+        assert!(matches!(from_ron("{\"a\": "), Err(Error::Decode(_))));
+    }
+}
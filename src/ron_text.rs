@@ -0,0 +1,87 @@
+use crate::json5::is_identifier;
+use crate::owned::unescape;
+use crate::{Error, Value};
+
+impl<'a> Value<'a> {
+    /// Writes the value as RON without the `ron` crate, with objects as
+    /// anonymous structs, e.g. `(name: "a", tags: ["x"], parent: None)`.
+    ///
+    /// Arrays are lists, `null` is `None` and strings are decoded and
+    /// re-escaped for RON. Numbers and booleans are written as they are.
+    /// Fails with `Error::Decode` if a key isn't a valid field name; use
+    /// `to_ron_string` to write objects as maps instead.
+    pub fn to_ron(&self) -> Result<String, Error> {
+        let mut out = String::new();
+        write_ron(self, &mut out)?;
+        return Ok(out);
+    }
+}
+
+fn write_ron(value: &Value, out: &mut String) -> Result<(), Error> {
+    match value {
+        Value::Null => out.push_str("None"),
+        Value::Boolean(b) => out.push_str(b),
+        Value::Number(n) => out.push_str(n),
+        Value::String(s) => {
+            out.push('"');
+            for c in unescape(s).chars() {
+                match c {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+        }
+        Value::Object(o) => {
+            out.push('(');
+            for (i, entry) in o.iter().enumerate() {
+                if !is_identifier(entry.key) {
+                    return Err(Error::Decode(format!("\"{}\" is not a valid RON field name", entry.key)));
+                }
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                out.push_str(entry.key);
+                out.push_str(": ");
+                write_ron(&entry.value, out)?;
+            }
+            out.push(')');
+        }
+        Value::Array(a) => {
+            out.push('[');
+            for (i, value) in a.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_ron(value, out)?;
+            }
+            out.push(']');
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Error};
+
+    #[test]
+    fn to_ron_test() {
+        let value = parse_value("{\"name\":\"a \\\"b\\\"\\n\",\"size\":1.5,\"visible\":true,\"parent\":null,\"pos\":{\"x\":1,\"y\":-2},\"tags\":[\"x\",{}]}").unwrap();
+        let ron = value.to_ron().unwrap();
+        assert_eq!(
+            ron,
+            "(name: \"a \\\"b\\\"\\n\", size: 1.5, visible: true, parent: None, pos: (x: 1, y: -2), tags: [\"x\", ()])"
+        );
+
+        #[cfg(feature = "ron")]
+        assert!(::ron::from_str::<::ron::Value>(&ron).is_ok(), "{}", ron);
+
+        let value = parse_value("{\"not-ident\":1}").unwrap();
+        assert!(matches!(value.to_ron(), Err(Error::Decode(_))));
+    }
+}
@@ -0,0 +1,56 @@
+use rand::seq::index;
+use rand::Rng;
+
+use crate::{OwnedArray, Value};
+
+impl<'a> Value<'a> {
+    /// Picks `n` random elements of an array. If `n` is at most the length
+    /// of the array, no element is picked twice and the sample keeps the
+    /// array's order. Otherwise elements are picked with replacement, in the
+    /// order they were drawn.
+    ///
+    /// Anything other than a non-empty array gives an empty sample.
+    pub fn random_sample<R: Rng>(&self, rng: &mut R, n: usize) -> OwnedArray {
+        let a = match self {
+            Value::Array(a) if !a.is_empty() => a,
+            _ => return OwnedArray::default(),
+        };
+
+        if n > a.len() {
+            return (0..n).map(|_| a[rng.gen_range(0..a.len())].to_owned()).collect();
+        }
+        let mut picked = index::sample(rng, a.len(), n).into_vec();
+        picked.sort_unstable();
+        return picked.into_iter().map(|i| a[i].to_owned()).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::{parse_value, Value};
+
+    #[test]
+    fn random_sample_test() {
+        let value = parse_value("[1,\"a\",true,null,{\"b\":2},[3]]").unwrap();
+        let elements: Vec<_> = value.as_array().unwrap().iter().map(Value::to_owned).collect();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for n in 0..=6 {
+            let sample = value.random_sample(&mut rng, n);
+            assert_eq!(sample.len(), n);
+            assert!(sample.iter().all(|v| elements.contains(v)));
+            let positions: Vec<_> = sample.iter().map(|v| elements.iter().position(|e| e == v).unwrap()).collect();
+            assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        }
+
+        let sample = value.random_sample(&mut rng, 20);
+        assert_eq!(sample.len(), 20);
+        assert!(sample.iter().all(|v| elements.contains(v)));
+
+        assert!(parse_value("[]").unwrap().random_sample(&mut rng, 3).is_empty());
+        assert!(parse_value("{\"a\":1}").unwrap().random_sample(&mut rng, 1).is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{parse_strict, pointer, Error, ObjectBuilder, OwnedEntry, OwnedValue, Value};
+
+/// A problem found while checking a document against a JSON Schema.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The document could not be parsed.
+    Parse(Error),
+    /// The value at `path` (an RFC 6901 pointer) violates the schema.
+    Invalid { path: String, message: String },
+}
+
+/// Parses `s` as strict JSON, as `parse_strict` does, and validates the
+/// result against `schema`.
+///
+/// A parse failure is reported as a single `SchemaError::Parse`; otherwise every
+/// violation found is returned.
+pub fn validate_and_parse<S: AsRef<str>>(s: S, schema: &OwnedValue) -> Result<OwnedValue, Vec<SchemaError>> {
+    let value = match parse_strict(s.as_ref()) {
+        Ok(v) => v,
+        Err(e) => return Err(vec![SchemaError::Parse(e)]),
+    };
+    value.validate(schema)?;
+    return Ok(value);
+}
+
+impl OwnedValue {
+    /// Validates the value against a JSON Schema.
+    ///
+    /// Only the `type`, `enum`, `required`, `properties` and `items` keywords are
+    /// checked; anything else in the schema is ignored.
+    pub fn validate(&self, schema: &OwnedValue) -> Result<(), Vec<SchemaError>> {
+        let mut errors = vec![];
+        validate(self, schema, "", &mut errors);
+        if errors.is_empty() {
+            return Ok(());
+        }
+        return Err(errors);
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Checks that there are no nulls anywhere in the tree, returning the
+    /// RFC 6901 pointers of any that are found.
+    pub fn validate_no_null(&self) -> Result<(), Vec<String>> {
+        let mut paths = vec![];
+        self.walk("", &mut |path, value| {
+            if let Value::Null = value {
+                paths.push(path.to_string());
+            }
+        });
+        if paths.is_empty() {
+            return Ok(());
+        }
+        return Err(paths);
+    }
+
+    /// Checks that every scalar in the tree is a string, returning the RFC 6901
+    /// pointers of any that aren't. Objects and arrays themselves, empty or not,
+    /// aren't leaves and are never reported.
+    pub fn validate_all_strings(&self) -> Result<(), Vec<String>> {
+        let mut paths = vec![];
+        self.walk("", &mut |path, value| match value {
+            Value::String(_) | Value::Object(_) | Value::Array(_) => {}
+            _ => paths.push(path.to_string()),
+        });
+        if paths.is_empty() {
+            return Ok(());
+        }
+        return Err(paths);
+    }
+
+    /// Pairs the values in this object with the type names given for them in
+    /// `schema`, an object of the same shape such as `{"name":"string"}`, as
+    /// `(pointer, value, type name)` triples.
+    ///
+    /// Nested schema objects are followed into nested objects. Keys missing
+    /// from either side, or given something other than a type name or an
+    /// object in the schema, are skipped.
+    pub fn zip_with_schema<'s>(&'s self, schema: &'s OwnedValue) -> Vec<(String, &'s Value<'a>, &'s str)> {
+        let mut zipped = vec![];
+        zip(self, schema, "", &mut zipped);
+        return zipped;
+    }
+
+    /// Summarizes the fields of a set of records, such as the lines of an
+    /// NDJSON file, as an object with an entry per field in the order fields
+    /// first appear. Each entry gives the type names seen for the field and
+    /// whether every record has it, e.g.
+    /// `{"id": {"types": ["number"], "required": true}}`.
+    ///
+    /// Records that aren't objects have no fields, so when there are any, no
+    /// field is required.
+    pub fn sample_schema(values: &[&Value]) -> OwnedValue {
+        let mut fields: Vec<(&str, Vec<&'static str>, usize)> = vec![];
+        for value in values.iter() {
+            let o = match value {
+                Value::Object(o) => o,
+                _ => continue,
+            };
+            for entry in o.iter() {
+                let index = match fields.iter().position(|(key, _, _)| *key == entry.key) {
+                    Some(i) => i,
+                    None => {
+                        fields.push((entry.key, vec![], 0));
+                        fields.len() - 1
+                    }
+                };
+                let (_, types, count) = &mut fields[index];
+                if !types.contains(&entry.value.type_name()) {
+                    types.push(entry.value.type_name());
+                }
+                *count += 1;
+            }
+        }
+
+        let entries = fields
+            .into_iter()
+            .map(|(key, types, count)| OwnedEntry {
+                key: key.to_string(),
+                value: OwnedValue::Object(
+                    ObjectBuilder::new()
+                        .insert("types", OwnedValue::Array(types.into_iter().map(OwnedValue::from).collect()))
+                        .insert("required", OwnedValue::from(count == values.len()))
+                        .build(),
+                ),
+            })
+            .collect();
+        return OwnedValue::Object(entries);
+    }
+}
+
+fn zip<'s, 'a>(value: &'s Value<'a>, schema: &'s OwnedValue, path: &str, zipped: &mut Vec<(String, &'s Value<'a>, &'s str)>) {
+    let fields = match schema {
+        OwnedValue::Object(o) => o,
+        _ => return,
+    };
+    for field in fields.iter() {
+        let value = match value.get(&field.key) {
+            Some(v) => v,
+            None => continue,
+        };
+        let path = pointer::push(path, &field.key);
+        match &field.value {
+            OwnedValue::String(t) => zipped.push((path, value, t.as_str())),
+            nested => zip(value, nested, &path, zipped),
+        }
+    }
+}
+
+fn invalid(errors: &mut Vec<SchemaError>, path: &str, message: String) {
+    errors.push(SchemaError::Invalid {
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn matches_type(value: &OwnedValue, t: &OwnedValue) -> bool {
+    return match t {
+        OwnedValue::String(t) if t == "integer" => match value {
+            OwnedValue::Number(n) => is_integer(n),
+            _ => false,
+        },
+        OwnedValue::String(t) => value.type_name() == t,
+        OwnedValue::Array(ts) => ts.iter().any(|t| matches_type(value, t)),
+        _ => false,
+    };
+}
+
+/// Whether the number `n` has no fractional part, so `1.0` and `1.5e1` are
+/// integers as JSON Schema counts them. Worked out from the digits, so it's
+/// exact however large `n` is.
+fn is_integer(n: &str) -> bool {
+    let (mantissa, exponent) = match n.find(&['e', 'E'][..]) {
+        Some(i) => {
+            let exponent = &n[i + 1..];
+            let overflow = if exponent.starts_with('-') { i64::MIN } else { i64::MAX };
+            (&n[..i], exponent.parse::<i64>().unwrap_or(overflow))
+        }
+        None => (n, 0),
+    };
+    let mantissa = mantissa.trim_start_matches('-');
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+
+    let point = (whole.len() as i64).saturating_add(exponent);
+    return whole
+        .chars()
+        .chain(fraction.chars())
+        .enumerate()
+        .all(|(i, c)| (i as i64) < point || c == '0');
+}
+
+fn validate(value: &OwnedValue, schema: &OwnedValue, path: &str, errors: &mut Vec<SchemaError>) {
+    let schema = match schema {
+        OwnedValue::Object(o) => o,
+        OwnedValue::Boolean(b) if b == "false" => {
+            return invalid(errors, path, "no value is allowed here".to_string());
+        }
+        _ => return,
+    };
+
+    for keyword in schema.iter() {
+        match (keyword.key.as_str(), &keyword.value) {
+            ("type", t) if !matches_type(value, t) => {
+                invalid(errors, path, format!("expected type {:?}, found {}", t, value.type_name()));
+            }
+            ("enum", OwnedValue::Array(options)) if !options.iter().any(|o| o == value) => {
+                invalid(errors, path, "value is not one of the allowed options".to_string());
+            }
+            ("required", OwnedValue::Array(keys)) => {
+                if let OwnedValue::Object(_) = value {
+                    for key in keys.iter() {
+                        if let OwnedValue::String(key) = key {
+                            if value.get(key).is_none() {
+                                invalid(errors, path, format!("missing required key \"{}\"", key));
+                            }
+                        }
+                    }
+                }
+            }
+            ("properties", OwnedValue::Object(properties)) => {
+                if let OwnedValue::Object(o) = value {
+                    for entry in o.iter() {
+                        if let Some(property) = properties.iter().find(|p| p.key == entry.key) {
+                            validate(&entry.value, &property.value, &pointer::push(path, &entry.key), errors);
+                        }
+                    }
+                }
+            }
+            ("items", items) => {
+                if let OwnedValue::Array(a) = value {
+                    for (i, item) in a.iter().enumerate() {
+                        validate(item, items, &pointer::push(path, &i.to_string()), errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, validate_and_parse, SchemaError, Value};
+
+    #[test]
+    fn validate_no_null_test() {
+        let value = parse_value("{\"a\":null,\"b\":[1,{\"c\":null}],\"d\":\"null\"}").unwrap();
+        assert_eq!(value.validate_no_null(), Err(vec!["/a".to_string(), "/b/1/c".to_string()]));
+
+        let value = parse_value("{\"a\":1,\"b\":[true,{\"c\":\"x\"}]}").unwrap();
+        assert_eq!(value.validate_no_null(), Ok(()));
+    }
+
+    #[test]
+    fn zip_with_schema_test() {
+        let value = parse_value("{\"name\":\"Alice\",\"age\":30,\"address\":{\"city\":\"x\",\"zip\":1},\"extra\":true}").unwrap();
+        let schema = parse_value("{\"name\":\"string\",\"age\":\"number\",\"address\":{\"city\":\"string\"},\"missing\":\"null\"}").unwrap().to_owned();
+
+        let zipped = value.zip_with_schema(&schema);
+        assert_eq!(
+            zipped,
+            vec![
+                ("/name".to_string(), &Value::String("Alice"), "string"),
+                ("/age".to_string(), &Value::Number("30"), "number"),
+                ("/address/city".to_string(), &Value::String("x"), "string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sample_schema_test() {
+        let lines = "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":null,\"tags\":[]}\n{\"id\":3,\"tags\":[\"x\"]}";
+        let records: Vec<Value> = lines.lines().map(|l| parse_value(l).unwrap()).collect();
+        let records: Vec<&Value> = records.iter().collect();
+
+        let expected = "{\"id\":{\"types\":[\"number\"],\"required\":true},\"name\":{\"types\":[\"string\",\"null\"],\"required\":false},\"tags\":{\"types\":[\"array\"],\"required\":false}}";
+        assert_eq!(Value::sample_schema(&records), parse_value(expected).unwrap().to_owned());
+        assert_eq!(Value::sample_schema(&[]), parse_value("{}").unwrap().to_owned());
+    }
+
+    const SCHEMA: &str = "{\"type\":\"object\",\"required\":[\"name\",\"age\"],\"properties\":{\"name\":{\"type\":\"string\"},\"age\":{\"type\":\"integer\"},\"tags\":{\"type\":\"array\",\"items\":{\"type\":\"string\"}}}}";
+
+    #[test]
+    fn validate_all_strings_test() {
+        let value = parse_value("{\"a\":\"x\",\"b\":1,\"c\":[\"y\",true,{}],\"d\":{\"e\":null,\"f\":\"z\"}}").unwrap();
+        let expected = vec!["/b".to_string(), "/c/1".to_string(), "/d/e".to_string()];
+        assert_eq!(value.validate_all_strings(), Err(expected));
+
+        let value = parse_value("{\"a\":\"x\",\"c\":[\"y\",[]]}").unwrap();
+        assert_eq!(value.validate_all_strings(), Ok(()));
+    }
+
+    #[test]
+    fn validate_and_parse_test() {
+        let schema = parse_value(SCHEMA).unwrap().to_owned();
+
+        let value = validate_and_parse("{\"name\":\"abcd\",\"age\":12,\"tags\":[\"a\"]}", &schema).unwrap();
+        assert_eq!(value.get("age").unwrap().type_name(), "number");
+
+        let errors = validate_and_parse("{\"name\":\"abcd", &schema).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], SchemaError::Parse(_)));
+
+        for malformed in ["{\"name\" \"abcd\",\"age\":1}", "{\"name\":\"abcd\" \"age\":1}", "{\"name\":\"abcd\",\"age\":1,}"].iter() {
+            let errors = validate_and_parse(malformed, &schema).unwrap_err();
+            assert!(matches!(errors[..], [SchemaError::Parse(_)]), "accepted {}", malformed);
+        }
+
+        for age in ["1.0", "12", "-3.000", "1.5e1", "1e3", "0.0e-5", "123456789012345678901234567890.0", "1.5e99999999999999999999"].iter() {
+            let s = format!("{{\"name\":\"abcd\",\"age\":{}}}", age);
+            assert!(validate_and_parse(&s, &schema).is_ok(), "rejected {}", age);
+        }
+        for age in ["1.5", "1.25e1", "1e-1", "-0.01", "1e-99999999999999999999"].iter() {
+            let s = format!("{{\"name\":\"abcd\",\"age\":{}}}", age);
+            assert!(validate_and_parse(&s, &schema).is_err(), "accepted {}", age);
+        }
+
+        let errors = validate_and_parse("{\"age\":1.5,\"tags\":[\"a\",2]}", &schema).unwrap_err();
+        let paths: Vec<&str> = errors
+            .iter()
+            .map(|e| match e {
+                SchemaError::Invalid { path, .. } => path.as_str(),
+                SchemaError::Parse(_) => panic!("unexpected parse error"),
+            })
+            .collect();
+        assert_eq!(paths, vec!["", "/age", "/tags/1"]);
+    }
+}
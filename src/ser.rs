@@ -0,0 +1,419 @@
+use serde::ser::{self, Serialize};
+
+use crate::owned::escape;
+use crate::{Error, OwnedEntry, OwnedValue};
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Error {
+        return Error::Decode(msg.to_string());
+    }
+}
+
+/// Converts anything that implements `Serialize` into an `OwnedValue`.
+pub fn to_owned_value<T: Serialize + ?Sized>(value: &T) -> Result<OwnedValue, Error> {
+    return value.serialize(ValueSerializer);
+}
+
+/// A `serde::Serializer` that builds an `OwnedValue`.
+///
+/// Structs and maps become objects and sequences and tuples become arrays.
+/// `None` and unit values become `null`, unit enum variants become their
+/// name, and other enum variants become an object holding their contents
+/// under their name. Map keys must serialize as strings or numbers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueSerializer;
+
+/// Collects the elements of a sequence, tuple or tuple variant.
+pub struct SerializeArray {
+    variant: Option<&'static str>,
+    values: Vec<OwnedValue>,
+}
+
+/// Collects the entries of a map, struct or struct variant.
+pub struct SerializeObject {
+    variant: Option<&'static str>,
+    entries: Vec<OwnedEntry>,
+    key: Option<String>,
+}
+
+/// Wraps `value` as `{variant: value}` if there is a variant.
+fn wrap(variant: Option<&'static str>, value: OwnedValue) -> OwnedValue {
+    return match variant {
+        Some(name) => OwnedValue::Object(Box::new([OwnedEntry {
+            key: escape(name),
+            value,
+        }])),
+        None => value,
+    };
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = OwnedValue;
+    type Error = Error;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v));
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as i64));
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as i64));
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as i64));
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v));
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Number(v.to_string()));
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as u64));
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as u64));
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v as u64));
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v));
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Number(v.to_string()));
+    }
+
+    /// Written with `f32` precision, so `0.1f32` is `0.1`.
+    fn serialize_f32(self, v: f32) -> Result<OwnedValue, Error> {
+        if !v.is_finite() {
+            return Ok(OwnedValue::Null);
+        }
+        return Ok(OwnedValue::Number(v.to_string()));
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v));
+    }
+
+    fn serialize_char(self, v: char) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v.to_string()));
+    }
+
+    fn serialize_str(self, v: &str) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(v));
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Array(v.iter().map(|b| OwnedValue::from(*b as u64)).collect()));
+    }
+
+    fn serialize_none(self) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Null);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<OwnedValue, Error> {
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Null);
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::Null);
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<OwnedValue, Error> {
+        return Ok(OwnedValue::from(variant));
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<OwnedValue, Error> {
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<OwnedValue, Error> {
+        return Ok(wrap(Some(variant), value.serialize(self)?));
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        return Ok(SerializeArray {
+            variant: None,
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        });
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray, Error> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        return Ok(SerializeArray {
+            variant: Some(variant),
+            values: Vec::with_capacity(len),
+        });
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, Error> {
+        return Ok(SerializeObject {
+            variant: None,
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        });
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        return self.serialize_map(Some(len));
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, Error> {
+        return Ok(SerializeObject {
+            variant: Some(variant),
+            entries: Vec::with_capacity(len),
+            key: None,
+        });
+    }
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(value.serialize(ValueSerializer)?);
+        return Ok(());
+    }
+
+    fn finish(self) -> Result<OwnedValue, Error> {
+        return Ok(wrap(self.variant, OwnedValue::Array(self.values.into_boxed_slice())));
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl SerializeObject {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.entries.push(OwnedEntry {
+            key,
+            value: value.serialize(ValueSerializer)?,
+        });
+        return Ok(());
+    }
+
+    fn finish(self) -> Result<OwnedValue, Error> {
+        return Ok(wrap(self.variant, OwnedValue::Object(self.entries.into_boxed_slice())));
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = match key.serialize(ValueSerializer)? {
+            OwnedValue::String(s) | OwnedValue::Number(s) => Some(s),
+            other => return Err(Error::Decode(format!("map keys must be strings, not {}", other.type_name()))),
+        };
+        return Ok(());
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Decode("map value serialized before its key".to_string()))?;
+        return self.insert(key, value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        return self.insert(escape(key), value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        return self.insert(escape(key), value);
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        return self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use crate::{parse_value, to_owned_value, Error, OwnedValue, ValueSerializer};
+
+    #[derive(Serialize)]
+    enum Role {
+        Admin,
+        Guest(u8),
+        Custom { name: String },
+    }
+
+    #[derive(Serialize)]
+    struct Address {
+        city: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Serialize)]
+    struct User {
+        name: String,
+        age: u32,
+        score: f64,
+        ratio: f32,
+        active: bool,
+        initial: char,
+        tags: Vec<&'static str>,
+        address: Address,
+        roles: Vec<Role>,
+        pair: (i8, ()),
+    }
+
+    #[test]
+    fn serialize_struct_test() {
+        let user = User {
+            name: "a \"b\"".to_string(),
+            age: 30,
+            score: -1.5,
+            ratio: 0.1,
+            active: true,
+            initial: 'a',
+            tags: vec!["x", "y"],
+            address: Address {
+                city: "c".to_string(),
+                zip: None,
+            },
+            roles: vec![Role::Admin, Role::Guest(2), Role::Custom { name: "r".to_string() }],
+            pair: (-1, ()),
+        };
+
+        let value = user.serialize(ValueSerializer).unwrap();
+        assert_eq!(value.get("name"), Some(&OwnedValue::from("a \"b\"")));
+        assert_eq!(value.get("age"), Some(&OwnedValue::from(30u64)));
+        assert_eq!(value.get("address").and_then(|a| a.get("zip")), Some(&OwnedValue::Null));
+
+        let expected = "{\"name\":\"a \\\"b\\\"\",\"age\":30,\"score\":-1.5,\"ratio\":0.1,\"active\":true,\"initial\":\"a\",\"tags\":[\"x\",\"y\"],\"address\":{\"city\":\"c\",\"zip\":null},\"roles\":[\"Admin\",{\"Guest\":2},{\"Custom\":{\"name\":\"r\"}}],\"pair\":[-1,null]}";
+        assert_eq!(value, parse_value(expected).unwrap().to_owned());
+    }
+
+    #[test]
+    fn serialize_map_test() {
+        let mut map = BTreeMap::new();
+        map.insert(1, "one");
+        map.insert(2, "two");
+        assert_eq!(to_owned_value(&map).unwrap(), parse_value("{\"1\":\"one\",\"2\":\"two\"}").unwrap().to_owned());
+
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], 1);
+        assert!(matches!(to_owned_value(&map), Err(Error::Decode(_))));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::Value;
+
+impl<'a> Value<'a> {
+    /// Writes the value as a Lisp style S-expression.
+    ///
+    /// Objects become lists of `("key" value)` pairs and arrays become plain
+    /// lists. Strings are double quoted with their escapes kept, booleans are
+    /// `#t` and `#f` and `null` is `nil`. An empty object and an empty array
+    /// are both `()`.
+    pub fn to_sexpr_string(&self) -> String {
+        return match self {
+            Value::Boolean(b) => if *b == "true" { "#t" } else { "#f" }.to_string(),
+            Value::String(s) => format!("\"{}\"", s),
+            Value::Number(n) => n.to_string(),
+            Value::Null => "nil".to_string(),
+            Value::Object(o) => {
+                let pairs: Vec<String> = o
+                    .iter()
+                    .map(|e| format!("(\"{}\" {})", e.key, e.value.to_sexpr_string()))
+                    .collect();
+                format!("({})", pairs.join(" "))
+            }
+            Value::Array(a) => {
+                let values: Vec<String> = a.iter().map(Value::to_sexpr_string).collect();
+                format!("({})", values.join(" "))
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_value;
+
+    /// Checks that the parentheses outside of strings balance.
+    fn balanced(sexpr: &str) -> bool {
+        let mut depth = 0i32;
+        let mut in_string = false;
+        let mut escaped = false;
+        for c in sexpr.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string => depth -= 1,
+                _ => {}
+            }
+            if depth < 0 {
+                return false;
+            }
+        }
+        return depth == 0 && !in_string;
+    }
+
+    #[test]
+    fn to_sexpr_string_test() {
+        let value = parse_value("{\"name\":\"a (b) \\\"c\\\"\",\"n\":1.5,\"tags\":[true,false,null],\"inner\":{\"x\":{},\"y\":[]}}").unwrap();
+        let sexpr = value.to_sexpr_string();
+
+        assert_eq!(
+            sexpr,
+            "((\"name\" \"a (b) \\\"c\\\"\") (\"n\" 1.5) (\"tags\" (#t #f nil)) (\"inner\" ((\"x\" ()) (\"y\" ()))))"
+        );
+        assert!(balanced(&sexpr));
+        assert_eq!(parse_value("\"x\"").unwrap().to_sexpr_string(), "\"x\"");
+    }
+}
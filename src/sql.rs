@@ -0,0 +1,101 @@
+use crate::owned::{escape, unescape};
+use crate::{parse_strict, OwnedEntry, OwnedValue, Value};
+
+impl<'a> Value<'a> {
+    /// Writes an object as a SQL `INSERT` into `table`, with a column per key,
+    /// e.g. `INSERT INTO users ("name", "age") VALUES ('Alice', 30)`.
+    ///
+    /// Strings are decoded and single quoted with apostrophes doubled, `null`
+    /// is `NULL` and booleans are `TRUE` and `FALSE`. Nested objects and arrays
+    /// are stored as their compact JSON text. Column names are decoded and
+    /// double quoted with quotes doubled, so any key is a valid column. The
+    /// table name is written as it is, since it may be qualified with a
+    /// schema. A value that isn't an object gives an empty string.
+    pub fn to_sql_insert(&self, table: &str) -> String {
+        let o = match self {
+            Value::Object(o) => o,
+            _ => return String::new(),
+        };
+
+        let columns: Vec<String> = o.iter().map(|e| sql_identifier(&unescape(e.key))).collect();
+        let values: Vec<String> = o
+            .iter()
+            .map(|e| match &e.value {
+                Value::Null => "NULL".to_string(),
+                Value::Boolean(b) => b.to_uppercase(),
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => sql_quote(&unescape(s)),
+                other => sql_quote(&other.to_string()),
+            })
+            .collect();
+        return format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), values.join(", "));
+    }
+
+    /// Builds an object from a database row, pairing each column name with
+    /// the value at the same position. Extra names or values are ignored.
+    ///
+    /// Values that are exactly a JSON number, boolean or `null`, with no
+    /// surrounding whitespace, are kept as such. Everything else becomes a
+    /// string.
+    pub fn from_sql_row(columns: &[&str], values: &[&str]) -> OwnedValue {
+        let entries = columns
+            .iter()
+            .zip(values.iter())
+            .map(|(column, value)| OwnedEntry {
+                key: escape(column),
+                value: match parse_strict(value) {
+                    Ok(scalar @ OwnedValue::Number(_)) | Ok(scalar @ OwnedValue::Boolean(_)) | Ok(scalar @ OwnedValue::Null)
+                        if value.trim() == *value =>
+                    {
+                        scalar
+                    }
+                    _ => OwnedValue::from(*value),
+                },
+            })
+            .collect();
+        return OwnedValue::Object(entries);
+    }
+}
+
+fn sql_quote(s: &str) -> String {
+    return format!("'{}'", s.replace('\'', "''"));
+}
+
+fn sql_identifier(s: &str) -> String {
+    return format!("\"{}\"", s.replace('"', "\"\""));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_value, Value};
+
+    #[test]
+    fn to_sql_insert_test() {
+        let value = parse_value("{\"name\": \"Alice\", \"age\": 30}").unwrap();
+        assert_eq!(value.to_sql_insert("users"), "INSERT INTO users (\"name\", \"age\") VALUES ('Alice', 30)");
+
+        let value = parse_value("{\"name\":\"O'Brien \\\"x\\\"\",\"score\":-1.5,\"admin\":false,\"manager\":null,\"tags\":[\"a\"]}").unwrap();
+        assert_eq!(
+            value.to_sql_insert("users"),
+            "INSERT INTO users (\"name\", \"score\", \"admin\", \"manager\", \"tags\") VALUES ('O''Brien \"x\"', -1.5, FALSE, NULL, '[\"a\"]')"
+        );
+        assert_eq!(parse_value("[1]").unwrap().to_sql_insert("users"), "");
+
+        let value = parse_value("{\"a) VALUES (1); DROP TABLE t; --\":1,\"order\":2,\"first name\":3,\"x\\\"y\":4}").unwrap();
+        assert_eq!(
+            value.to_sql_insert("t"),
+            "INSERT INTO t (\"a) VALUES (1); DROP TABLE t; --\", \"order\", \"first name\", \"x\"\"y\") VALUES (1, 2, 3, 4)"
+        );
+    }
+
+    #[test]
+    fn from_sql_row_test() {
+        let columns = ["id", "name", "score", "active", "manager", "zip", "note", "tags"];
+        let values = ["7", "Alice", "-1.5e2", "true", "null", "01234", " 5 ", "[1]"];
+        let expected = "{\"id\":7,\"name\":\"Alice\",\"score\":-1.5e2,\"active\":true,\"manager\":null,\"zip\":\"01234\",\"note\":\" 5 \",\"tags\":\"[1]\"}";
+        assert_eq!(Value::from_sql_row(&columns, &values), parse_value(expected).unwrap().to_owned());
+
+        let row = Value::from_sql_row(&["a\"b", "c"], &["x\"y"]);
+        assert_eq!(row, parse_value("{\"a\\\"b\":\"x\\\"y\"}").unwrap().to_owned());
+    }
+}
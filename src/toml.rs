@@ -0,0 +1,134 @@
+use ::toml::value::{Array, Table};
+use ::toml::Value as TomlValue;
+
+use crate::owned::{escape, unescape};
+use crate::{Error, OwnedEntry, OwnedValue, Value};
+
+impl<'a> Value<'a> {
+    /// Converts the value to a `toml::Value`.
+    ///
+    /// Objects become tables, keeping their order, with the last value
+    /// winning for a repeated key. Numbers become integers when they fit in
+    /// an `i64` and floats otherwise. String escapes are decoded. Fails with
+    /// `Error::Decode` for `null`, which TOML has no way to write, and for
+    /// arrays mixing types, which TOML doesn't allow.
+    pub fn to_toml(&self) -> Result<TomlValue, Error> {
+        return match self {
+            Value::Null => Err(Error::Decode("TOML has no null".to_string())),
+            Value::Boolean(b) => Ok(TomlValue::Boolean(*b == "true")),
+            Value::String(s) => Ok(TomlValue::String(unescape(s))),
+            Value::Number(n) => match n.parse::<i64>() {
+                Ok(i) => Ok(TomlValue::Integer(i)),
+                Err(_) => Ok(TomlValue::Float(n.parse::<f64>().unwrap_or(f64::NAN))),
+            },
+            Value::Object(o) => {
+                let mut table = Table::new();
+                for entry in o.iter() {
+                    table.insert(unescape(entry.key), entry.value.to_toml()?);
+                }
+                Ok(TomlValue::Table(table))
+            }
+            Value::Array(a) => {
+                let array = a.iter().map(Value::to_toml).collect::<Result<Array, _>>()?;
+                if let Some(first) = array.first() {
+                    if let Some(other) = array.iter().find(|v| v.type_str() != first.type_str()) {
+                        return Err(Error::Decode(format!("a TOML array can't hold both {} and {} values", first.type_str(), other.type_str())));
+                    }
+                }
+                Ok(TomlValue::Array(array))
+            }
+        };
+    }
+
+    /// Converts a `toml::Value`, the inverse of `to_toml`. Tables keep their
+    /// order and datetimes become strings in TOML's format. Strings are
+    /// escaped as needed, and the float `nan` and infinities, which JSON
+    /// can't represent, become `null`.
+    pub fn from_toml(v: &TomlValue) -> OwnedValue {
+        return match v {
+            TomlValue::String(s) => OwnedValue::from(s.as_str()),
+            TomlValue::Integer(i) => OwnedValue::Number(i.to_string()),
+            TomlValue::Float(f) => OwnedValue::from(*f),
+            TomlValue::Boolean(b) => OwnedValue::from(*b),
+            TomlValue::Datetime(d) => OwnedValue::from(d.to_string()),
+            TomlValue::Array(a) => OwnedValue::Array(a.iter().map(Value::from_toml).collect()),
+            TomlValue::Table(t) => OwnedValue::Object(
+                t.iter()
+                    .map(|(key, value)| OwnedEntry {
+                        key: escape(key),
+                        value: Value::from_toml(value),
+                    })
+                    .collect(),
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::toml::Value as TomlValue;
+
+    use crate::{parse_value, Error, OwnedValue, Value};
+
+    #[test]
+    fn to_toml_test() {
+        let config = parse_value("{\"title\":\"TOML \\\"example\\\"\",\"owner\":{\"name\":\"Tom\",\"admin\":true},\"database\":{\"ports\":[8000,8001,8002],\"connection_max\":5000,\"load\":0.75,\"enabled\":false},\"servers\":[{\"name\":\"alpha\",\"ip\":\"10.0.0.1\"},{\"name\":\"beta\",\"ip\":\"10.0.0.2\"}],\"matrix\":[[1,2],[\"a\"]]}").unwrap();
+        let expected = r#"
+            title = 'TOML "example"'
+            matrix = [[1, 2], ["a"]]
+
+            [owner]
+            name = "Tom"
+            admin = true
+
+            [database]
+            ports = [8000, 8001, 8002]
+            connection_max = 5000
+            load = 0.75
+            enabled = false
+
+            [[servers]]
+            name = "alpha"
+            ip = "10.0.0.1"
+
+            [[servers]]
+            name = "beta"
+            ip = "10.0.0.2"
+        "#;
+        let toml = config.to_toml().unwrap();
+        assert_eq!(toml, expected.parse::<TomlValue>().unwrap());
+        assert_eq!(toml.as_table().unwrap().keys().collect::<Vec<_>>(), vec!["title", "owner", "database", "servers", "matrix"]);
+
+        assert_eq!(parse_value("1e400").unwrap().to_toml().unwrap(), TomlValue::Float(f64::INFINITY));
+
+        let rejected = ["null", "{\"a\":{\"b\":null}}", "[1,\"a\"]", "[1,2.5]", "[{\"a\":1},[1]]"];
+        for s in rejected.iter() {
+            assert!(matches!(parse_value(s).unwrap().to_toml(), Err(Error::Decode(_))), "accepted {}", s);
+        }
+    }
+
+    #[test]
+    fn from_toml_test() {
+        let toml = r#"
+            name = "jstr \"tests\""
+            version = 3
+            ratio = 0.5
+            published = true
+            released = 1979-05-27T07:32:00Z
+            nan = nan
+
+            [deps.serde]
+            version = "1"
+            features = ["derive"]
+        "#
+        .parse::<TomlValue>()
+        .unwrap();
+        let expected = parse_value("{\"name\":\"jstr \\\"tests\\\"\",\"version\":3,\"ratio\":0.5,\"published\":true,\"released\":\"1979-05-27T07:32:00Z\",\"nan\":null,\"deps\":{\"serde\":{\"version\":\"1\",\"features\":[\"derive\"]}}}").unwrap();
+        assert_eq!(Value::from_toml(&toml), expected.to_owned());
+
+        let value = parse_value("{\"a\":\"x\\ny\",\"b\":[1,2],\"c\":{\"d\":-1.25,\"e\":[{\"f\":false}]}}").unwrap();
+        let text = ::toml::to_string(&value.to_toml().unwrap()).unwrap();
+        assert_eq!(Value::from_toml(&text.parse::<TomlValue>().unwrap()), value.to_owned());
+        assert_eq!(Value::from_toml(&TomlValue::Float(f64::INFINITY)), OwnedValue::Null);
+    }
+}
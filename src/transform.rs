@@ -0,0 +1,395 @@
+use crate::{OwnedEntry, OwnedObject, OwnedValue, Value};
+
+impl OwnedValue {
+    /// Copies the object with `old_prefix` replaced by `new_prefix` at the start
+    /// of every top level key. Other keys are left alone, and a value that isn't
+    /// an object gives an empty object.
+    pub fn replace_key_prefix(&self, old_prefix: &str, new_prefix: &str) -> OwnedObject {
+        let o = match self {
+            OwnedValue::Object(o) => o,
+            _ => return OwnedObject::default(),
+        };
+
+        return o
+            .iter()
+            .map(|entry| OwnedEntry {
+                key: match entry.key.strip_prefix(old_prefix) {
+                    Some(rest) => format!("{}{}", new_prefix, rest),
+                    None => entry.key.clone(),
+                },
+                value: entry.value.clone(),
+            })
+            .collect();
+    }
+
+    /// Flattens nested arrays into one, so `[[1,[2,3]],4,[5]]` becomes
+    /// `[1,2,3,4,5]`. Objects are copied as they are, arrays inside them
+    /// included, and a value that isn't an array is returned unchanged.
+    pub fn flatten_arrays(&self) -> OwnedValue {
+        return match self {
+            OwnedValue::Array(a) => {
+                let mut flat = vec![];
+                flatten_into(a, &mut flat);
+                OwnedValue::Array(flat.into_boxed_slice())
+            }
+            other => other.clone(),
+        };
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Copies the value with every `null` in the tree replaced by an empty
+    /// string.
+    pub fn stringify_nulls_as_empty_string(&self) -> OwnedValue {
+        return to_owned_with(self, &|v| match v {
+            Value::Null => Some(OwnedValue::String(String::new())),
+            _ => None,
+        });
+    }
+
+    /// Copies the value with every boolean in the tree replaced by `1` for
+    /// `true` or `0` for `false`.
+    pub fn stringify_booleans_as_numbers(&self) -> OwnedValue {
+        return to_owned_with(self, &|v| match v {
+            Value::Boolean(b) => Some(OwnedValue::Number(if *b == "true" { "1" } else { "0" }.to_string())),
+            _ => None,
+        });
+    }
+
+    /// Copies the value with every empty string, array and object in the tree
+    /// replaced by `null`. Containers are checked before their contents are,
+    /// so `[[]]` becomes `[null]`.
+    pub fn stringify_empty_values(&self) -> OwnedValue {
+        return to_owned_with(self, &|v| match v {
+            Value::String("") => Some(OwnedValue::Null),
+            Value::Array(a) if a.is_empty() => Some(OwnedValue::Null),
+            Value::Object(o) if o.is_empty() => Some(OwnedValue::Null),
+            _ => None,
+        });
+    }
+
+    /// Expands compact IRIs in object keys using the prefixes of a JSON-LD
+    /// context, so with `{"ex": "http://example.org/"}` the key `ex:name`
+    /// becomes `http://example.org/name`.
+    ///
+    /// `context` may be the context object itself or an object holding it
+    /// under `@context`. Any `@context` in the document is applied too, to the
+    /// object holding it and everything inside, and then removed. Only prefix
+    /// expansion is done; other JSON-LD processing isn't.
+    pub fn apply_json_ld_context(&self, context: &OwnedValue) -> OwnedValue {
+        return apply_context(self, &mut context_prefixes(context));
+    }
+
+    /// Copies the value with every character outside printable ASCII in its
+    /// strings written as a `\uXXXX` escape, using surrogate pairs beyond the
+    /// Basic Multilingual Plane. The text is unchanged, only its encoding.
+    /// Object keys are left alone.
+    pub fn encode_special_chars(&self) -> OwnedValue {
+        return to_owned_with(self, &|v| match v {
+            Value::String(s) => Some(OwnedValue::String(encode_special_chars(s))),
+            _ => None,
+        });
+    }
+
+    /// Copies the value with every number that has a fraction or exponent
+    /// rewritten in its shortest form, so `1.0` becomes `1`, `3.140` becomes
+    /// `3.14` and `1.000e3` becomes `1000`.
+    ///
+    /// These numbers are read as `f64`, so digits beyond its precision are
+    /// dropped. Integers are left as they are, as are numbers too large for
+    /// an `f64`.
+    pub fn compact_numbers(&self) -> OwnedValue {
+        return to_owned_with(self, &|v| match v {
+            Value::Number(n) if n.contains(&['.', 'e', 'E'][..]) => Some(OwnedValue::Number(compact_number(n))),
+            _ => None,
+        });
+    }
+
+    /// Copies the value with the value of every entry for one of
+    /// `sensitive_keys`, at any depth, replaced by the string `"[REDACTED]"`.
+    /// Keys are matched exactly as they appear in the document, escapes
+    /// included.
+    pub fn redact(&self, sensitive_keys: &[&str]) -> OwnedValue {
+        return match self {
+            Value::Object(o) => OwnedValue::Object(
+                o.iter()
+                    .map(|entry| OwnedEntry {
+                        key: entry.key.to_string(),
+                        value: if sensitive_keys.contains(&entry.key) {
+                            OwnedValue::String("[REDACTED]".to_string())
+                        } else {
+                            entry.value.redact(sensitive_keys)
+                        },
+                    })
+                    .collect(),
+            ),
+            Value::Array(a) => OwnedValue::Array(a.iter().map(|v| v.redact(sensitive_keys)).collect()),
+            other => other.to_owned(),
+        };
+    }
+
+    /// Copies the value with every entry whose key starts with `$` or `@`
+    /// removed at any depth, as used for metadata by JSON Schema, JSON-LD and
+    /// MongoDB.
+    pub fn strip_metadata_keys(&self) -> OwnedValue {
+        return self.remove_all_matching_keys(|key| key.starts_with(&['$', '@'][..]));
+    }
+
+    /// Copies the value with every entry whose key satisfies `pred` removed
+    /// at any depth. Keys are given as written, escapes included.
+    pub fn remove_all_matching_keys<F: Fn(&str) -> bool>(&self, pred: F) -> OwnedValue {
+        return remove_matching_keys(self, &pred);
+    }
+}
+
+/// The prefixes defined by a JSON-LD context object, along with the IRIs
+/// they stand for. Entries that aren't strings define no prefix.
+fn context_prefixes(context: &OwnedValue) -> Vec<(String, String)> {
+    let o = match context.get("@context").unwrap_or(context) {
+        OwnedValue::Object(o) => o,
+        _ => return vec![],
+    };
+    return o
+        .iter()
+        .filter_map(|e| match &e.value {
+            OwnedValue::String(iri) => Some((e.key.clone(), iri.clone())),
+            _ => None,
+        })
+        .collect();
+}
+
+/// Expands the compact IRI `key` if its prefix is one of `prefixes`. Later
+/// prefixes win, so inner contexts override outer ones.
+fn expand_key(key: &str, prefixes: &[(String, String)]) -> String {
+    if let Some(i) = key.find(':') {
+        let (prefix, suffix) = (&key[..i], &key[i + 1..]);
+        if !suffix.starts_with("//") {
+            if let Some((_, iri)) = prefixes.iter().rev().find(|(p, _)| p == prefix) {
+                return format!("{}{}", iri, suffix);
+            }
+        }
+    }
+    return key.to_string();
+}
+
+fn apply_context(value: &Value, prefixes: &mut Vec<(String, String)>) -> OwnedValue {
+    return match value {
+        Value::Object(o) => {
+            let outer = prefixes.len();
+            if let Some(context) = value.get("@context") {
+                prefixes.extend(context_prefixes(&context.to_owned()));
+            }
+            let expanded = o
+                .iter()
+                .filter(|e| e.key != "@context")
+                .map(|e| OwnedEntry {
+                    key: expand_key(e.key, prefixes),
+                    value: apply_context(&e.value, prefixes),
+                })
+                .collect();
+            prefixes.truncate(outer);
+            OwnedValue::Object(expanded)
+        }
+        Value::Array(a) => OwnedValue::Array(a.iter().map(|v| apply_context(v, prefixes)).collect()),
+        other => other.to_owned(),
+    };
+}
+
+fn encode_special_chars(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for c in s.chars() {
+        if (' '..='~').contains(&c) {
+            encoded.push(c);
+            continue;
+        }
+        let mut units = [0u16; 2];
+        for unit in c.encode_utf16(&mut units).iter() {
+            encoded.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    return encoded;
+}
+
+/// Writes `n` as plain decimal, or in exponent form when that's too long.
+fn compact_number(n: &str) -> String {
+    let f = match n.parse::<f64>() {
+        Ok(f) if f.is_finite() => f,
+        _ => return n.to_string(),
+    };
+
+    let plain = f.to_string();
+    if plain.len() <= n.len() {
+        return plain;
+    }
+    let exponent = format!("{:e}", f);
+    return if exponent.len() < plain.len() { exponent } else { plain };
+}
+
+/// Copies `value` into an `OwnedValue`, using whatever `f` returns in place of
+/// any value it maps. Objects and arrays that `f` doesn't map are copied with
+/// their contents passed through `f` in turn.
+fn to_owned_with(value: &Value, f: &dyn Fn(&Value) -> Option<OwnedValue>) -> OwnedValue {
+    if let Some(mapped) = f(value) {
+        return mapped;
+    }
+
+    return match value {
+        Value::Object(o) => OwnedValue::Object(
+            o.iter()
+                .map(|entry| OwnedEntry {
+                    key: entry.key.to_string(),
+                    value: to_owned_with(&entry.value, f),
+                })
+                .collect(),
+        ),
+        Value::Array(a) => OwnedValue::Array(a.iter().map(|v| to_owned_with(v, f)).collect()),
+        other => other.to_owned(),
+    };
+}
+
+fn remove_matching_keys(value: &Value, pred: &dyn Fn(&str) -> bool) -> OwnedValue {
+    return match value {
+        Value::Object(o) => OwnedValue::Object(
+            o.iter()
+                .filter(|entry| !pred(entry.key))
+                .map(|entry| OwnedEntry {
+                    key: entry.key.to_string(),
+                    value: remove_matching_keys(&entry.value, pred),
+                })
+                .collect(),
+        ),
+        Value::Array(a) => OwnedValue::Array(a.iter().map(|v| remove_matching_keys(v, pred)).collect()),
+        other => other.to_owned(),
+    };
+}
+
+fn flatten_into(a: &[OwnedValue], flat: &mut Vec<OwnedValue>) {
+    for value in a.iter() {
+        match value {
+            OwnedValue::Array(inner) => flatten_into(inner, flat),
+            other => flat.push(other.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::owned::unescape;
+    use crate::{parse_value, OwnedValue};
+
+    #[test]
+    fn replace_key_prefix_test() {
+        let value = parse_value("{\"foo_a\":1,\"foo_b\":2,\"bar\":3}").unwrap().to_owned();
+        let replaced = value.replace_key_prefix("foo_", "baz_");
+
+        let expected = parse_value("{\"baz_a\":1,\"baz_b\":2,\"bar\":3}").unwrap().to_owned();
+        assert_eq!(OwnedValue::Object(replaced), expected);
+    }
+
+    fn owned(s: &str) -> OwnedValue {
+        return parse_value(s).unwrap().to_owned();
+    }
+
+    #[test]
+    fn flatten_arrays_test() {
+        assert_eq!(owned("[[1,[2,3]],4,[5]]").flatten_arrays(), owned("[1,2,3,4,5]"));
+        assert_eq!(owned("[1,[2,\"a\"]]").flatten_arrays(), owned("[1,2,\"a\"]"));
+        assert_eq!(owned("[[{\"a\":[[1]]}],[]]").flatten_arrays(), owned("[{\"a\":[[1]]}]"));
+        assert_eq!(owned("{\"a\":[[1]]}").flatten_arrays(), owned("{\"a\":[[1]]}"));
+    }
+
+    #[test]
+    fn stringify_nulls_as_empty_string_test() {
+        let value = parse_value("{\"a\":null,\"b\":[1,null],\"c\":{\"d\":null,\"e\":\"x\"}}").unwrap();
+        assert_eq!(
+            value.stringify_nulls_as_empty_string(),
+            owned("{\"a\":\"\",\"b\":[1,\"\"],\"c\":{\"d\":\"\",\"e\":\"x\"}}")
+        );
+        assert_eq!(parse_value("null").unwrap().stringify_nulls_as_empty_string(), owned("\"\""));
+    }
+
+    #[test]
+    fn stringify_booleans_as_numbers_test() {
+        let value = parse_value("{\"a\":true,\"b\":[false,{\"c\":true}],\"d\":\"true\"}").unwrap();
+        assert_eq!(
+            value.stringify_booleans_as_numbers(),
+            owned("{\"a\":1,\"b\":[0,{\"c\":1}],\"d\":\"true\"}")
+        );
+    }
+
+    #[test]
+    fn compact_numbers_test() {
+        let value = parse_value("{\"a\":1.0,\"b\":3.140,\"c\":[1.000e3,-0.50,1E+2],\"d\":12345678901234567890,\"e\":\"1.0\"}").unwrap();
+        let compacted = value.compact_numbers();
+        assert_eq!(compacted, owned("{\"a\":1,\"b\":3.14,\"c\":[1000,-0.5,100],\"d\":12345678901234567890,\"e\":\"1.0\"}"));
+        assert!(format!("{:?}", compacted).len() < value.to_string().len());
+
+        let value = parse_value("[1.0e300,1.0e-7,2.5,1e999]").unwrap();
+        assert_eq!(value.compact_numbers(), owned("[1e300,1e-7,2.5,1e999]"));
+    }
+
+    #[test]
+    fn stringify_empty_values_test() {
+        let value = parse_value("{\"a\":\"\",\"b\":[],\"c\":{},\"d\":[\"x\",[[]],{\"e\":\"\"}],\"f\":0,\"g\":null}").unwrap();
+        assert_eq!(
+            value.stringify_empty_values(),
+            owned("{\"a\":null,\"b\":null,\"c\":null,\"d\":[\"x\",[null],{\"e\":null}],\"f\":0,\"g\":null}")
+        );
+        assert_eq!(parse_value("{}").unwrap().stringify_empty_values(), OwnedValue::Null);
+    }
+
+    #[test]
+    fn apply_json_ld_context_test() {
+        let value = parse_value("{\"@context\":{\"ex\":\"http://example.org/\"},\"ex:name\":\"Alice\"}").unwrap();
+        assert_eq!(value.apply_json_ld_context(&OwnedValue::Null), owned("{\"http://example.org/name\":\"Alice\"}"));
+
+        let context = owned("{\"@context\":{\"foaf\":\"http://xmlns.com/foaf/0.1/\",\"ex\":\"http://example.org/\"}}");
+        let value = parse_value("{\"foaf:knows\":[{\"foaf:name\":\"Bob\",\"@context\":{\"ex\":\"urn:ex:\"},\"ex:age\":3}],\"ex:id\":1,\"other:x\":2,\"http://a.b/c\":3,\"plain\":4}").unwrap();
+        let expected = "{\"http://xmlns.com/foaf/0.1/knows\":[{\"http://xmlns.com/foaf/0.1/name\":\"Bob\",\"urn:ex:age\":3}],\"http://example.org/id\":1,\"other:x\":2,\"http://a.b/c\":3,\"plain\":4}";
+        assert_eq!(value.apply_json_ld_context(&context), owned(expected));
+    }
+
+    #[test]
+    fn encode_special_chars_test() {
+        let value = parse_value("{\"emoji\":\"hi 😀\",\"é\":[\"caf\\u00e9 é\",\"tab\\there\u{1}\u{7f}\"],\"n\":1}").unwrap();
+        let encoded = value.encode_special_chars();
+        assert_eq!(
+            encoded,
+            owned("{\"emoji\":\"hi \\ud83d\\ude00\",\"é\":[\"caf\\u00e9 \\u00e9\",\"tab\\there\\u0001\\u007f\"],\"n\":1}")
+        );
+
+        match encoded.get("emoji") {
+            Some(OwnedValue::String(s)) => assert_eq!(unescape(s), "hi 😀"),
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn redact_test() {
+        let value = parse_value("{\"user\":\"jdoe\",\"password\":\"hunter2\",\"session\":{\"token\":\"abc\",\"expires\":3600},\"cards\":[{\"number\":\"4111111111111111\",\"brand\":\"visa\"},{\"number\":{\"last4\":\"1881\"},\"brand\":\"amex\"}],\"audit\":[{\"password\":null}]}").unwrap();
+        let expected = owned("{\"user\":\"jdoe\",\"password\":\"[REDACTED]\",\"session\":{\"token\":\"[REDACTED]\",\"expires\":3600},\"cards\":[{\"number\":\"[REDACTED]\",\"brand\":\"visa\"},{\"number\":\"[REDACTED]\",\"brand\":\"amex\"}],\"audit\":[{\"password\":\"[REDACTED]\"}]}");
+        assert_eq!(value.redact(&["password", "token", "number"]), expected);
+
+        assert_eq!(value.redact(&[]), value.to_owned());
+        assert_eq!(parse_value("[\"password\"]").unwrap().redact(&["password"]), owned("[\"password\"]"));
+    }
+
+    #[test]
+    fn strip_metadata_keys_test() {
+        let value = parse_value("{\"name\":\"Alice\",\"$schema\":\"http://json-schema.org/draft-07/schema#\",\"@context\":\"https://schema.org\"}").unwrap();
+        assert_eq!(value.strip_metadata_keys(), owned("{\"name\":\"Alice\"}"));
+
+        let value = parse_value("{\"_id\":{\"$oid\":\"5f1\"},\"tags\":[{\"@id\":\"x\",\"v\":1},\"$literal\"],\"a@b\":true}").unwrap();
+        assert_eq!(value.strip_metadata_keys(), owned("{\"_id\":{},\"tags\":[{\"v\":1},\"$literal\"],\"a@b\":true}"));
+    }
+
+    #[test]
+    fn remove_all_matching_keys_test() {
+        let value = parse_value("{\"id\":1,\"_rev\":\"2-a\",\"items\":[{\"_tmp\":true,\"name\":\"x\",\"meta\":{\"_seen\":1,\"tags\":[\"_kept\"]}},3],\"nested\":{\"_all\":{\"gone\":1}}}").unwrap();
+        let expected = owned("{\"id\":1,\"items\":[{\"name\":\"x\",\"meta\":{\"tags\":[\"_kept\"]}},3],\"nested\":{}}");
+        assert_eq!(value.remove_all_matching_keys(|key| key.starts_with('_')), expected);
+
+        assert_eq!(value.remove_all_matching_keys(|_| false), value.to_owned());
+        assert_eq!(value.remove_all_matching_keys(|_| true), owned("{}"));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::json5::is_identifier;
+use crate::OwnedValue;
+
+impl OwnedValue {
+    /// Describes the value as a TypeScript type, e.g. `{ a: number; b: string[] }`.
+    ///
+    /// Arrays of mixed types get a union element type, and empty arrays are
+    /// `unknown[]` since there is nothing to infer from.
+    pub fn to_typescript_type(&self) -> String {
+        return match self {
+            OwnedValue::Boolean(_) => "boolean".to_string(),
+            OwnedValue::String(_) => "string".to_string(),
+            OwnedValue::Number(_) => "number".to_string(),
+            OwnedValue::Null => "null".to_string(),
+            OwnedValue::Object(o) if o.is_empty() => "{}".to_string(),
+            OwnedValue::Object(o) => {
+                let fields: Vec<String> = o
+                    .iter()
+                    .map(|e| {
+                        if is_identifier(&e.key) {
+                            format!("{}: {}", e.key, e.value.to_typescript_type())
+                        } else {
+                            format!("\"{}\": {}", e.key, e.value.to_typescript_type())
+                        }
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            OwnedValue::Array(a) => {
+                let mut types: Vec<String> = vec![];
+                for t in a.iter().map(OwnedValue::to_typescript_type) {
+                    if !types.contains(&t) {
+                        types.push(t);
+                    }
+                }
+                match types.len() {
+                    0 => "unknown[]".to_string(),
+                    1 => format!("{}[]", types[0]),
+                    _ => format!("({})[]", types.join(" | ")),
+                }
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_value;
+
+    fn ts(s: &str) -> String {
+        return parse_value(s).unwrap().to_owned().to_typescript_type();
+    }
+
+    #[test]
+    fn to_typescript_type_test() {
+        assert_eq!(ts("\"hello\""), "string");
+        assert_eq!(ts("42"), "number");
+        assert_eq!(ts("true"), "boolean");
+        assert_eq!(ts("null"), "null");
+        assert_eq!(ts("[1,2]"), "number[]");
+        assert_eq!(ts("[]"), "unknown[]");
+        assert_eq!(ts("[1,\"a\",2]"), "(number | string)[]");
+        assert_eq!(ts("{}"), "{}");
+        assert_eq!(ts("{\"a\":1,\"b\":\"x\"}"), "{ a: number; b: string }");
+        assert_eq!(ts("{\"a-b\":[{\"c\":null}]}"), "{ \"a-b\": { c: null }[] }");
+    }
+}